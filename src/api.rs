@@ -1,25 +1,40 @@
-#[derive(Debug, PartialEq, Eq)]
+use crate::error::{ParseError, ParseErrorKind};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Namespace {
     name: String,
+    description: Option<String>,
+    availability: Availability,
     types: Vec<Type>,
     properties: Vec<Property>,
     methods: Vec<Method>,
+    events: Vec<Event>,
+    permissions: Vec<String>,
+    optional_permissions: Vec<String>,
+    manifest_keys: Vec<String>,
 }
 
 impl Namespace {
     pub(crate) fn new(
         name: String,
+        description: Option<String>,
         types: Vec<Type>,
-        mut properties: Vec<Property>,
+        properties: Vec<Property>,
         methods: Vec<Method>,
         events: Vec<Event>,
     ) -> Self {
-        properties.extend(events.into_iter().map(Event::into));
         Namespace {
             name,
+            description,
             types,
             properties,
             methods,
+            events,
+            availability: Availability::default(),
+            permissions: vec![],
+            optional_permissions: vec![],
+            manifest_keys: vec![],
         }
     }
 
@@ -27,17 +42,129 @@ impl Namespace {
         &self.name
     }
 
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn availability(&self) -> &Availability {
+        &self.availability
+    }
+
+    pub fn with_availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
     pub fn types(&self) -> &[Type] {
         &self.types
     }
 
+    pub fn types_mut(&mut self) -> &mut [Type] {
+        &mut self.types
+    }
+
     pub fn properties(&self) -> &[Property] {
         &self.properties
     }
 
+    pub fn properties_mut(&mut self) -> &mut [Property] {
+        &mut self.properties
+    }
+
     pub fn methods(&self) -> &[Method] {
         &self.methods
     }
+
+    pub fn methods_mut(&mut self) -> &mut [Method] {
+        &mut self.methods
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn events_mut(&mut self) -> &mut [Event] {
+        &mut self.events
+    }
+
+    pub fn permissions(&self) -> &[String] {
+        &self.permissions
+    }
+
+    pub fn optional_permissions(&self) -> &[String] {
+        &self.optional_permissions
+    }
+
+    pub fn manifest_keys(&self) -> &[String] {
+        &self.manifest_keys
+    }
+
+    pub fn with_permissions(
+        mut self,
+        permissions: Vec<String>,
+        optional_permissions: Vec<String>,
+    ) -> Self {
+        self.permissions = permissions;
+        self.optional_permissions = optional_permissions;
+        self
+    }
+
+    pub fn with_manifest_keys(mut self, manifest_keys: Vec<String>) -> Self {
+        self.manifest_keys = manifest_keys;
+        self
+    }
+
+    // Firefox spreads some namespaces over several schema files.
+    pub(crate) fn merge(&mut self, other: Namespace) {
+        if self.description.is_none() {
+            self.description = other.description;
+        }
+        if self.availability == Availability::default() {
+            self.availability = other.availability;
+        }
+        self.types.extend(other.types);
+        self.properties.extend(other.properties);
+        self.methods.extend(other.methods);
+        self.events.extend(other.events);
+        for (names, others) in [
+            (&mut self.permissions, other.permissions),
+            (&mut self.optional_permissions, other.optional_permissions),
+            (&mut self.manifest_keys, other.manifest_keys),
+        ] {
+            for name in others {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+
+    // Copies the members of a namespace named by `$import`, keeping those defined here.
+    pub(crate) fn import(&mut self, other: &Namespace) {
+        if self.description.is_none() {
+            self.description = other.description.clone();
+        }
+        for t in &other.types {
+            if self.types.iter().all(|own| own.name != t.name) {
+                self.types.push(t.clone());
+            }
+        }
+        for property in &other.properties {
+            if self.properties.iter().all(|own| own.name != property.name) {
+                self.properties.push(property.clone());
+            }
+        }
+        for method in &other.methods {
+            if self.methods.iter().all(|own| own.name != method.name) {
+                self.methods.push(method.clone());
+            }
+        }
+        for event in &other.events {
+            if self.events.iter().all(|own| own.name != event.name) {
+                self.events.push(event.clone());
+            }
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -49,7 +176,7 @@ pub(crate) enum ApiType {
 }
 
 impl std::convert::TryFrom<&str> for ApiType {
-    type Error = &'static str;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
@@ -57,122 +184,488 @@ impl std::convert::TryFrom<&str> for ApiType {
             "properties" => Ok(ApiType::Properties),
             "methods" => Ok(ApiType::Methods),
             "events" => Ok(ApiType::Events),
-            _ => Err("Unsupported API type"),
+            _ => Err(ParseError::new(ParseErrorKind::UnknownSection(
+                value.to_owned(),
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+    Dev,
+    Canary,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Availability {
+    since: Option<u32>,
+    channel: Channel,
+    deprecated: Option<String>,
+    platforms: Vec<String>,
+    contexts: Vec<String>,
+    min_manifest_version: Option<u32>,
+    max_manifest_version: Option<u32>,
+}
+
+impl Availability {
+    pub fn new(
+        since: Option<u32>,
+        channel: Channel,
+        deprecated: Option<String>,
+        platforms: Vec<String>,
+    ) -> Availability {
+        Availability {
+            since,
+            channel,
+            deprecated,
+            platforms,
+            contexts: vec![],
+            min_manifest_version: None,
+            max_manifest_version: None,
         }
     }
+
+    // Firefox limits some APIs to contexts such as "content" or "devtools".
+    pub fn with_contexts(mut self, contexts: Vec<String>) -> Self {
+        self.contexts = contexts;
+        self
+    }
+
+    pub fn with_manifest_versions(mut self, min: Option<u32>, max: Option<u32>) -> Self {
+        self.min_manifest_version = min;
+        self.max_manifest_version = max;
+        self
+    }
+
+    pub fn since(&self) -> Option<u32> {
+        self.since
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated.is_some()
+    }
+
+    pub fn deprecation(&self) -> Option<&str> {
+        self.deprecated.as_deref()
+    }
+
+    // Empty when the item is available on every platform.
+    pub fn platforms(&self) -> &[String] {
+        &self.platforms
+    }
+
+    pub fn is_available_in(&self, version: u32) -> bool {
+        self.since.is_none_or(|since| since <= version)
+    }
+
+    // Empty when the item is available in every context.
+    pub fn contexts(&self) -> &[String] {
+        &self.contexts
+    }
+
+    pub fn min_manifest_version(&self) -> Option<u32> {
+        self.min_manifest_version
+    }
+
+    pub fn max_manifest_version(&self) -> Option<u32> {
+        self.max_manifest_version
+    }
+
+    pub fn is_available_in_manifest(&self, manifest_version: u32) -> bool {
+        self.min_manifest_version
+            .is_none_or(|min| min <= manifest_version)
+            && self
+                .max_manifest_version
+                .is_none_or(|max| manifest_version <= max)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Primitive {
+    Integer,
+    Number,
+    Boolean,
+    String,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Element {
-    type_name: String,
-    is_array: bool,
-    name: String,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypeRef {
+    Primitive(Primitive),
+    Named(String),
+    Array(Box<TypeRef>),
+    Union(Vec<TypeRef>),
+    Enum(Vec<String>),
+    Object(Option<Box<Type>>),
+    Function,
+    Any,
+    Binary,
 }
 
-impl Element {
-    pub fn new(type_name: String, name: String) -> Self {
-        let type_info: Vec<_> = type_name.split(" ").collect();
+impl TypeRef {
+    pub fn parse(type_name: &str) -> Result<TypeRef, ParseError> {
+        let type_name = strip_parens(type_name.trim());
+
+        if let Some(values) = type_name.strip_prefix("enum of ") {
+            return Ok(TypeRef::Enum(parse_enum_values(values)));
+        }
+
+        let alternatives = split_union(type_name);
+        if alternatives.len() > 1 {
+            return alternatives
+                .iter()
+                .map(|t| TypeRef::parse(t))
+                .collect::<Result<_, _>>()
+                .map(TypeRef::union);
+        }
 
-        if name.contains(" ") {
-            dbg!(&type_name);
-            panic!("invalid name");
+        if let Some(item) = type_name.strip_prefix("array of ") {
+            return Ok(TypeRef::Array(Box::new(TypeRef::parse(item)?)));
         }
 
-        if type_info.len() == 1 {
-            Element {
-                type_name: type_name,
-                is_array: false,
-                name,
+        match type_name {
+            "integer" | "long" => Ok(TypeRef::Primitive(Primitive::Integer)),
+            "number" | "double" => Ok(TypeRef::Primitive(Primitive::Number)),
+            "boolean" => Ok(TypeRef::Primitive(Primitive::Boolean)),
+            "string" => Ok(TypeRef::Primitive(Primitive::String)),
+            "object" => Ok(TypeRef::Object(None)),
+            "function" => Ok(TypeRef::Function),
+            "any" => Ok(TypeRef::Any),
+            "binary" | "ArrayBuffer" => Ok(TypeRef::Binary),
+            _ if !type_name.is_empty()
+                && type_name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') =>
+            {
+                Ok(TypeRef::Named(type_name.to_owned()))
             }
-        } else if type_info.len() == 3 && type_info[0] == "array" && type_info[1] == "of" {
-            Element {
-                type_name: type_info[2].to_owned(),
-                is_array: true,
-                name,
+            _ => Err(ParseError::new(ParseErrorKind::UnsupportedType(
+                type_name.to_owned(),
+            ))),
+        }
+    }
+
+    pub fn union(alternatives: Vec<TypeRef>) -> TypeRef {
+        let mut flattened: Vec<TypeRef> = vec![];
+        for alternative in alternatives {
+            let nested = match alternative {
+                TypeRef::Union(nested) => nested,
+                other => vec![other],
+            };
+            for t in nested {
+                if !flattened.contains(&t) {
+                    flattened.push(t);
+                }
             }
-        } else if (type_info.len() == 3 && type_info[0] == "enum" && type_info[1] == "of")
-            || (&type_info[1..]).iter().any(|&w| w == "or")
-        {
-            Element {
-                type_name: "object".to_owned(),
-                is_array: false,
-                name,
+        }
+
+        if flattened.len() == 1 {
+            flattened.remove(0)
+        } else {
+            TypeRef::Union(flattened)
+        }
+    }
+
+    pub fn alternatives(&self) -> &[TypeRef] {
+        match self {
+            TypeRef::Union(alternatives) => alternatives,
+            other => std::slice::from_ref(other),
+        }
+    }
+
+    pub fn rustify(&self) -> String {
+        match self {
+            TypeRef::Primitive(Primitive::Integer) => "isize".to_owned(),
+            TypeRef::Primitive(Primitive::Number) => "f64".to_owned(),
+            TypeRef::Primitive(Primitive::Boolean) => "bool".to_owned(),
+            TypeRef::Primitive(Primitive::String) | TypeRef::Enum(_) => "String".to_owned(),
+            TypeRef::Named(name) => name.clone(),
+            TypeRef::Array(item) => format!("Vec<{}>", item.rustify()),
+            TypeRef::Binary => "ArrayBuffer".to_owned(),
+            TypeRef::Function => "function".to_owned(),
+            TypeRef::Object(Some(t)) => t.name().to_owned(),
+            TypeRef::Union(_) | TypeRef::Object(None) | TypeRef::Any => "object".to_owned(),
+        }
+    }
+
+    pub(crate) fn inline_object_mut(&mut self) -> Option<&mut TypeRef> {
+        match self {
+            TypeRef::Object(None) => Some(self),
+            TypeRef::Array(item) => item.inline_object_mut(),
+            TypeRef::Union(alternatives) => alternatives
+                .iter_mut()
+                .find_map(|alternative| alternative.inline_object_mut()),
+            _ => None,
+        }
+    }
+
+    fn ends_with_enum(&self) -> bool {
+        match self {
+            TypeRef::Enum(_) => true,
+            TypeRef::Array(item) => item.ends_with_enum(),
+            _ => false,
+        }
+    }
+}
+
+// Alternatives are separated by "or" and commas, binding looser than "array of", so
+// "integer or array of integer" is a union whose second member is an array. An
+// "enum of" list swallows the rest of the text since its values use the same separators,
+// and parentheses group anything else.
+fn split_union(type_name: &str) -> Vec<String> {
+    let mut alternatives = vec![];
+    let mut current: Vec<&str> = vec![];
+    let mut depth = 0i32;
+    let mut words = type_name.split_whitespace().peekable();
+
+    while let Some(word) = words.next() {
+        if depth == 0 && word == "enum" && words.peek() == Some(&"of") {
+            current.push(word);
+            current.extend(words.by_ref());
+            break;
+        }
+
+        let was_nested = depth > 0;
+        depth += word.matches('(').count() as i32 - word.matches(')').count() as i32;
+
+        if was_nested || depth > 0 {
+            current.push(word);
+        } else if word == "or" {
+            if !current.is_empty() {
+                alternatives.push(current.join(" "));
+                current.clear();
             }
+        } else if let Some(word) = word.strip_suffix(',') {
+            current.push(word);
+            alternatives.push(current.join(" "));
+            current.clear();
         } else {
-            dbg!(&type_name);
-            panic!("unsupported type");
+            current.push(word);
         }
     }
+    if !current.is_empty() {
+        alternatives.push(current.join(" "));
+    }
 
-    pub fn rustify_type(&self) -> &str {
-        match self.type_name.as_str() {
-            "integer" => "isize",
-            "number" => "f64",
-            "boolean" => "bool",
-            "string" => "String",
-            _ => &self.type_name,
+    alternatives
+}
+
+fn strip_parens(type_name: &str) -> &str {
+    if !type_name.starts_with('(') || !type_name.ends_with(')') {
+        return type_name;
+    }
+
+    let mut depth = 0;
+    for (i, c) in type_name.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 && i + 1 < type_name.len() {
+            return type_name;
+        }
+    }
+
+    strip_parens(type_name[1..type_name.len() - 1].trim())
+}
+
+impl std::fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TypeRef::Primitive(Primitive::Integer) => write!(f, "integer"),
+            TypeRef::Primitive(Primitive::Number) => write!(f, "number"),
+            TypeRef::Primitive(Primitive::Boolean) => write!(f, "boolean"),
+            TypeRef::Primitive(Primitive::String) => write!(f, "string"),
+            TypeRef::Named(name) => write!(f, "{}", name),
+            TypeRef::Array(item) => match **item {
+                TypeRef::Union(_) => write!(f, "array of ({})", item),
+                _ => write!(f, "array of {}", item),
+            },
+            TypeRef::Union(alternatives) => {
+                let last = alternatives.len().saturating_sub(1);
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", if alternatives.len() > 2 { ", " } else { " " })?;
+                    }
+                    if i == last && i > 0 {
+                        write!(f, "or ")?;
+                    }
+                    if i != last && alternative.ends_with_enum() {
+                        write!(f, "({})", alternative)?;
+                    } else {
+                        write!(f, "{}", alternative)?;
+                    }
+                }
+                Ok(())
+            }
+            TypeRef::Enum(values) => {
+                write!(f, "enum of ")?;
+                let last = values.len().saturating_sub(1);
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", if values.len() > 2 { ", " } else { " " })?;
+                    }
+                    if i == last && i > 0 {
+                        write!(f, "or ")?;
+                    }
+                    write!(f, "\"{}\"", value)?;
+                }
+                Ok(())
+            }
+            TypeRef::Object(_) => write!(f, "object"),
+            TypeRef::Function => write!(f, "function"),
+            TypeRef::Any => write!(f, "any"),
+            TypeRef::Binary => write!(f, "binary"),
         }
     }
+}
+
+fn parse_enum_values(values: &str) -> Vec<String> {
+    values
+        .split(',')
+        .flat_map(|v| v.split(" or "))
+        .map(|v| v.trim())
+        .map(|v| v.strip_prefix("or ").unwrap_or(v))
+        .map(|v| v.trim_matches('"'))
+        .filter(|v| !v.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Element {
+    type_ref: TypeRef,
+    name: String,
+    description: Option<String>,
+    availability: Availability,
+}
+
+impl Element {
+    pub fn new(
+        type_ref: TypeRef,
+        name: String,
+        description: Option<String>,
+    ) -> Result<Self, ParseError> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(ParseError::new(ParseErrorKind::InvalidName(name)));
+        }
+
+        Ok(Element {
+            type_ref,
+            name,
+            description,
+            availability: Availability::default(),
+        })
+    }
+
+    pub fn type_ref(&self) -> &TypeRef {
+        &self.type_ref
+    }
+
+    pub fn type_ref_mut(&mut self) -> &mut TypeRef {
+        &mut self.type_ref
+    }
+
+    pub fn rustify_type(&self) -> String {
+        self.type_ref.rustify()
+    }
 
     pub fn is_array(&self) -> bool {
-        self.is_array
+        matches!(self.type_ref, TypeRef::Array(_))
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn availability(&self) -> &Availability {
+        &self.availability
+    }
+
+    pub fn with_availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Type {
     name: String,
+    description: Option<String>,
+    availability: Availability,
     kind: TypeKind,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeKind {
-    Enum,
+    Enum {
+        variants: Vec<EnumVariant>,
+    },
     Data,
     Struct {
         elements: Vec<Element>,
         optional_elements: Vec<Element>,
         methods: Vec<Method>,
+        events: Vec<Event>,
     },
 }
 
 impl Type {
-    pub fn new_enum(name: String) -> Self {
+    pub fn new_enum(name: String, description: Option<String>, variants: Vec<EnumVariant>) -> Self {
         Type {
             name,
-            kind: TypeKind::Enum,
+            description,
+            kind: TypeKind::Enum { variants },
+            availability: Availability::default(),
         }
     }
 
-    pub fn new_data(name: String) -> Self {
+    pub fn new_data(name: String, description: Option<String>) -> Self {
         Type {
             name,
+            description,
             kind: TypeKind::Data,
+            availability: Availability::default(),
         }
     }
 
     pub(crate) fn new_struct(
         name: String,
+        description: Option<String>,
         elements: Vec<Element>,
         optional_elements: Vec<Element>,
-        mut methods: Vec<Method>,
+        methods: Vec<Method>,
         events: Vec<Event>,
     ) -> Self {
-        methods.extend(events.into_iter().map(|e| Method {
-            name: format!("{}.{}", e.event_name, e.add_listener.name),
-            args: e.add_listener.args,
-        }));
         Type {
             name,
+            description,
             kind: TypeKind::Struct {
                 elements,
                 optional_elements,
                 methods,
+                events,
             },
+            availability: Availability::default(),
         }
     }
 
@@ -180,12 +673,91 @@ impl Type {
         &self.name
     }
 
+    pub(crate) fn rename(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn availability(&self) -> &Availability {
+        &self.availability
+    }
+
+    pub fn with_availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
     pub fn kind(&self) -> &TypeKind {
         &self.kind
     }
+
+    pub fn kind_mut(&mut self) -> &mut TypeKind {
+        &mut self.kind
+    }
+
+    // Adds the members of `other` to this type, as Firefox's `$extend` does. Returns
+    // false, changing nothing, if the kinds differ.
+    pub(crate) fn extend(&mut self, other: Type) -> bool {
+        match (&mut self.kind, other.kind) {
+            (
+                TypeKind::Struct {
+                    elements,
+                    optional_elements,
+                    methods,
+                    events,
+                },
+                TypeKind::Struct {
+                    elements: other_elements,
+                    optional_elements: other_optional_elements,
+                    methods: other_methods,
+                    events: other_events,
+                },
+            ) => {
+                elements.extend(other_elements);
+                optional_elements.extend(other_optional_elements);
+                methods.extend(other_methods);
+                events.extend(other_events);
+                true
+            }
+            (TypeKind::Enum { variants }, TypeKind::Enum { variants: others }) => {
+                for variant in others {
+                    if !variants.iter().any(|v| v.value() == variant.value()) {
+                        variants.push(variant);
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumVariant {
+    value: String,
+    description: Option<String>,
+}
+
+impl EnumVariant {
+    pub fn new(value: String, description: Option<String>) -> EnumVariant {
+        EnumVariant { value, description }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Argument {
     kind: ArgumentKind,
     optioned: bool,
@@ -210,87 +782,376 @@ impl Argument {
         self.optioned
     }
 
+    pub fn description(&self) -> Option<&str> {
+        match &self.kind {
+            ArgumentKind::Element { element } => element.description(),
+            ArgumentKind::Callback { callback } => callback.description(),
+        }
+    }
+
+    pub fn availability(&self) -> &Availability {
+        match &self.kind {
+            ArgumentKind::Element { element } => element.availability(),
+            ArgumentKind::Callback { callback } => callback.availability(),
+        }
+    }
+
     pub fn kind(&self) -> &ArgumentKind {
         &self.kind
     }
+
+    pub fn kind_mut(&mut self) -> &mut ArgumentKind {
+        &mut self.kind
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArgumentKind {
     Element { element: Element },
     Callback { callback: Method },
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Returns {
+    type_ref: TypeRef,
+    description: Option<String>,
+}
+
+impl Returns {
+    pub fn new(type_ref: TypeRef, description: Option<String>) -> Returns {
+        Returns {
+            type_ref,
+            description,
+        }
+    }
+
+    pub fn type_ref(&self) -> &TypeRef {
+        &self.type_ref
+    }
+
+    pub fn type_ref_mut(&mut self) -> &mut TypeRef {
+        &mut self.type_ref
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Method {
     name: String,
     args: Vec<Argument>,
+    returns: Option<Returns>,
+    supports_promise: bool,
+    permissions: Vec<String>,
+    description: Option<String>,
+    availability: Availability,
 }
 
 impl Method {
-    pub fn new(name: String, args: Vec<Argument>) -> Method {
-        Method { name, args }
+    pub fn new(
+        name: String,
+        args: Vec<Argument>,
+        returns: Option<Returns>,
+        supports_promise: bool,
+        description: Option<String>,
+    ) -> Method {
+        Method {
+            name,
+            args,
+            returns,
+            supports_promise,
+            permissions: vec![],
+            description,
+            availability: Availability::default(),
+        }
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn availability(&self) -> &Availability {
+        &self.availability
+    }
+
+    pub fn with_availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
     pub fn args(&self) -> &[Argument] {
         &self.args
     }
+
+    pub fn args_mut(&mut self) -> &mut [Argument] {
+        &mut self.args
+    }
+
+    pub fn returns(&self) -> Option<&Returns> {
+        self.returns.as_ref()
+    }
+
+    pub fn returns_mut(&mut self) -> Option<&mut Returns> {
+        self.returns.as_mut()
+    }
+
+    pub fn supports_promise(&self) -> bool {
+        self.supports_promise
+    }
+
+    pub fn permissions(&self) -> &[String] {
+        &self.permissions
+    }
+
+    pub fn with_permissions(mut self, permissions: Vec<String>) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn callback(&self) -> Option<&Method> {
+        match self.args.last().map(Argument::kind) {
+            Some(ArgumentKind::Callback { callback }) => Some(callback),
+            _ => None,
+        }
+    }
+
+    pub fn promise_args(&self) -> &[Argument] {
+        if self.supports_promise && self.callback().is_some() {
+            &self.args[..self.args.len() - 1]
+        } else {
+            &self.args
+        }
+    }
+
+    // The callback parameters a promise resolves with when the trailing callback is
+    // omitted, or `None` when the method doesn't return a promise.
+    pub fn promise_params(&self) -> Option<&[Argument]> {
+        if !self.supports_promise {
+            return None;
+        }
+        Some(self.callback().map_or(&[], Method::args))
+    }
+
+    // The value a promise resolves to. A callback without parameters resolves to
+    // nothing, so `None` is returned for it as well. Several parameters resolve to an
+    // array of them in order, typed here as an array of their union; see
+    // `promise_params` for each one's own type.
+    pub fn promise_type(&self) -> Option<TypeRef> {
+        let types = self
+            .promise_params()?
+            .iter()
+            .map(|arg| match arg.kind() {
+                ArgumentKind::Element { element } => element.type_ref().clone(),
+                ArgumentKind::Callback { .. } => TypeRef::Function,
+            })
+            .collect::<Vec<_>>();
+        match types.len() {
+            0 => None,
+            1 => types.into_iter().next(),
+            _ => Some(TypeRef::Array(Box::new(TypeRef::union(types)))),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub(crate) struct Event {
-    event_name: String,
-    add_listener: Method,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Event {
+    name: String,
+    callback: Method,
+    extra_args: Vec<Argument>,
+    description: Option<String>,
+    availability: Availability,
 }
 
 impl Event {
-    pub fn new(event_name: String, add_listener: Method) -> Event {
+    pub(crate) fn new(name: String, add_listener: Method, description: Option<String>) -> Event {
+        let mut callback = None;
+        let mut extra_args = vec![];
+        for arg in add_listener.args {
+            match arg.kind {
+                ArgumentKind::Callback { callback: c } if callback.is_none() => callback = Some(c),
+                kind => extra_args.push(Argument {
+                    kind,
+                    optioned: arg.optioned,
+                }),
+            }
+        }
+
         Event {
-            event_name,
-            add_listener,
+            name,
+            callback: callback
+                .unwrap_or_else(|| Method::new("callback".to_owned(), vec![], None, false, None)),
+            extra_args,
+            description,
+            availability: Availability::default(),
         }
     }
-}
 
-impl std::convert::Into<Property> for Event {
-    fn into(self) -> Property {
-        Property {
-            name: self.event_name,
-            kind: PropertyKind::Object {
-                methods: vec![self.add_listener],
-            },
-        }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn availability(&self) -> &Availability {
+        &self.availability
+    }
+
+    pub fn with_availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    pub fn callback(&self) -> &Method {
+        &self.callback
+    }
+
+    pub fn callback_mut(&mut self) -> &mut Method {
+        &mut self.callback
+    }
+
+    pub fn extra_args(&self) -> &[Argument] {
+        &self.extra_args
+    }
+
+    pub fn extra_args_mut(&mut self) -> &mut [Argument] {
+        &mut self.extra_args
+    }
+
+    pub fn add_listener(&self) -> Method {
+        let mut args = vec![Argument::new_callback(self.callback.clone(), false)];
+        args.extend(self.extra_args.iter().cloned());
+        Method::new("addListener".to_owned(), args, None, false, None)
+    }
+
+    pub fn remove_listener(&self) -> Method {
+        let args = vec![Argument::new_callback(self.callback.clone(), false)];
+        Method::new("removeListener".to_owned(), args, None, false, None)
+    }
+
+    pub fn has_listener(&self) -> Method {
+        let args = vec![Argument::new_callback(self.callback.clone(), false)];
+        let returns = Returns::new(TypeRef::Primitive(Primitive::Boolean), None);
+        Method::new("hasListener".to_owned(), args, Some(returns), false, None)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Property {
     name: String,
+    description: Option<String>,
+    availability: Availability,
     kind: PropertyKind,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyKind {
-    Immediate { type_name: String },
-    Object { methods: Vec<Method> },
+    Immediate {
+        type_ref: TypeRef,
+        value: Option<Literal>,
+    },
+    Object {
+        methods: Vec<Method>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Literal {
+    Integer(i64),
+    Float(Float),
+    String(String),
+}
+
+// A float that is neither NaN nor infinite, so that equality on it is total.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "f64", into = "f64")
+)]
+pub struct Float(f64);
+
+impl Eq for Float {}
+
+impl Float {
+    pub fn new(value: f64) -> Option<Float> {
+        Some(Float(value)).filter(|_| value.is_finite())
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl std::convert::TryFrom<f64> for Float {
+    type Error = &'static str;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Float::new(value).ok_or("float constants must be finite")
+    }
+}
+
+impl From<Float> for f64 {
+    fn from(value: Float) -> f64 {
+        value.0
+    }
+}
+
+impl Literal {
+    pub fn type_ref(&self) -> TypeRef {
+        match self {
+            Literal::Integer(_) => TypeRef::Primitive(Primitive::Integer),
+            Literal::Float(_) => TypeRef::Primitive(Primitive::Number),
+            Literal::String(_) => TypeRef::Primitive(Primitive::String),
+        }
+    }
+}
+
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Literal::Integer(i) => write!(f, "{}", i),
+            Literal::Float(x) => write!(f, "{:?}", x.get()),
+            Literal::String(s) => write!(f, "{:?}", s),
+        }
+    }
 }
 
 impl Property {
-    pub fn new_immediate(name: String, type_name: String) -> Property {
+    pub fn new_immediate(
+        name: String,
+        type_ref: TypeRef,
+        value: Option<Literal>,
+        description: Option<String>,
+    ) -> Property {
         Property {
             name,
-            kind: PropertyKind::Immediate { type_name },
+            description,
+            kind: PropertyKind::Immediate { type_ref, value },
+            availability: Availability::default(),
         }
     }
 
-    pub fn new_object(name: String, methods: Vec<Method>) -> Property {
+    pub fn new_object(name: String, methods: Vec<Method>, description: Option<String>) -> Property {
         Property {
             name,
+            description,
             kind: PropertyKind::Object { methods },
+            availability: Availability::default(),
         }
     }
 
@@ -298,7 +1159,31 @@ impl Property {
         &self.name
     }
 
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn availability(&self) -> &Availability {
+        &self.availability
+    }
+
+    pub fn with_availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
     pub fn kind(&self) -> &PropertyKind {
         &self.kind
     }
+
+    pub fn kind_mut(&mut self) -> &mut PropertyKind {
+        &mut self.kind
+    }
+
+    pub fn value(&self) -> Option<&Literal> {
+        match &self.kind {
+            PropertyKind::Immediate { value, .. } => value.as_ref(),
+            PropertyKind::Object { .. } => None,
+        }
+    }
 }
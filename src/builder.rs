@@ -0,0 +1,582 @@
+use crate::api::*;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BuildError {
+    EmptyName {
+        kind: &'static str,
+    },
+    InvalidName {
+        kind: &'static str,
+        name: String,
+    },
+    DuplicateName {
+        kind: &'static str,
+        name: String,
+        parent: String,
+    },
+    // A member that the kind being built can't have, such as a variant of a struct.
+    Mismatch {
+        member: &'static str,
+        kind: &'static str,
+        name: String,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::EmptyName { kind } => write!(f, "{} name must not be empty", kind),
+            BuildError::InvalidName { kind, name } => {
+                write!(f, "{} name {:?} is not a valid identifier", kind, name)
+            }
+            BuildError::DuplicateName { kind, name, parent } => {
+                write!(f, "duplicate {} {} in {}", kind, name, parent)
+            }
+            BuildError::Mismatch { member, kind, name } => {
+                write!(f, "{} {} cannot have {}s", kind, name, member)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+fn check_name(kind: &'static str, name: &str, dotted: bool) -> Result<(), BuildError> {
+    if name.is_empty() {
+        return Err(BuildError::EmptyName { kind });
+    }
+    let valid = !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'))
+        && (dotted || !name.contains('.'));
+    if !valid {
+        return Err(BuildError::InvalidName {
+            kind,
+            name: name.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+fn check_unique<'a>(
+    kind: &'static str,
+    parent: &str,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<(), BuildError> {
+    let mut seen = HashSet::new();
+    for name in names {
+        if !seen.insert(name) {
+            return Err(BuildError::DuplicateName {
+                kind,
+                name: name.to_owned(),
+                parent: parent.to_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn arg_name(arg: &Argument) -> &str {
+    match arg.kind() {
+        ArgumentKind::Element { element } => element.name(),
+        ArgumentKind::Callback { callback } => callback.name(),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NamespaceBuilder {
+    name: String,
+    description: Option<String>,
+    availability: Availability,
+    types: Vec<Type>,
+    properties: Vec<Property>,
+    methods: Vec<Method>,
+    events: Vec<Event>,
+    permissions: Vec<String>,
+    optional_permissions: Vec<String>,
+    manifest_keys: Vec<String>,
+}
+
+impl NamespaceBuilder {
+    pub fn new(name: &str) -> Self {
+        NamespaceBuilder {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    pub fn availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    pub fn type_(mut self, t: Type) -> Self {
+        self.types.push(t);
+        self
+    }
+
+    pub fn property(mut self, property: Property) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    pub fn method(mut self, method: Method) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    pub fn event(mut self, event: Event) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    pub fn permission(mut self, permission: &str) -> Self {
+        self.permissions.push(permission.to_owned());
+        self
+    }
+
+    pub fn optional_permission(mut self, permission: &str) -> Self {
+        self.optional_permissions.push(permission.to_owned());
+        self
+    }
+
+    pub fn manifest_key(mut self, key: &str) -> Self {
+        self.manifest_keys.push(key.to_owned());
+        self
+    }
+
+    pub fn build(self) -> Result<Namespace, BuildError> {
+        check_name("namespace", &self.name, true)?;
+        check_unique("type", &self.name, self.types.iter().map(Type::name))?;
+        // Properties, methods and events all live on the same JavaScript object.
+        check_unique(
+            "member",
+            &self.name,
+            self.properties
+                .iter()
+                .map(Property::name)
+                .chain(self.methods.iter().map(Method::name))
+                .chain(self.events.iter().map(Event::name)),
+        )?;
+
+        Ok(Namespace::new(
+            self.name,
+            self.description,
+            self.types,
+            self.properties,
+            self.methods,
+            self.events,
+        )
+        .with_availability(self.availability)
+        .with_permissions(self.permissions, self.optional_permissions)
+        .with_manifest_keys(self.manifest_keys))
+    }
+}
+
+#[derive(Debug)]
+enum TypeBuilderKind {
+    Enum(Vec<EnumVariant>),
+    Data,
+    Struct {
+        elements: Vec<Element>,
+        optional_elements: Vec<Element>,
+        methods: Vec<Method>,
+        events: Vec<Event>,
+    },
+}
+
+impl TypeBuilderKind {
+    fn name(&self) -> &'static str {
+        match self {
+            TypeBuilderKind::Enum(_) => "enum",
+            TypeBuilderKind::Data => "data type",
+            TypeBuilderKind::Struct { .. } => "struct",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TypeBuilder {
+    name: String,
+    description: Option<String>,
+    availability: Availability,
+    kind: TypeBuilderKind,
+    mismatch: Option<&'static str>,
+}
+
+impl TypeBuilder {
+    fn new(name: &str, kind: TypeBuilderKind) -> Self {
+        TypeBuilder {
+            name: name.to_owned(),
+            description: None,
+            availability: Availability::default(),
+            kind,
+            mismatch: None,
+        }
+    }
+
+    fn mismatch(mut self, member: &'static str) -> Self {
+        self.mismatch.get_or_insert(member);
+        self
+    }
+
+    pub fn new_enum(name: &str) -> Self {
+        TypeBuilder::new(name, TypeBuilderKind::Enum(vec![]))
+    }
+
+    pub fn new_data(name: &str) -> Self {
+        TypeBuilder::new(name, TypeBuilderKind::Data)
+    }
+
+    pub fn new_struct(name: &str) -> Self {
+        TypeBuilder::new(
+            name,
+            TypeBuilderKind::Struct {
+                elements: vec![],
+                optional_elements: vec![],
+                methods: vec![],
+                events: vec![],
+            },
+        )
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    pub fn availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    // Variants only fit builders created with `new_enum`, and elements, methods and
+    // events those created with `new_struct`; anything else fails `build`.
+    pub fn variant(mut self, value: &str, description: Option<&str>) -> Self {
+        match &mut self.kind {
+            TypeBuilderKind::Enum(variants) => variants.push(EnumVariant::new(
+                value.to_owned(),
+                description.map(str::to_owned),
+            )),
+            _ => return self.mismatch("enum variant"),
+        }
+        self
+    }
+
+    pub fn element(mut self, element: Element) -> Self {
+        match &mut self.kind {
+            TypeBuilderKind::Struct { elements, .. } => elements.push(element),
+            _ => return self.mismatch("element"),
+        }
+        self
+    }
+
+    pub fn optional_element(mut self, element: Element) -> Self {
+        match &mut self.kind {
+            TypeBuilderKind::Struct {
+                optional_elements, ..
+            } => optional_elements.push(element),
+            _ => return self.mismatch("element"),
+        }
+        self
+    }
+
+    pub fn method(mut self, method: Method) -> Self {
+        match &mut self.kind {
+            TypeBuilderKind::Struct { methods, .. } => methods.push(method),
+            _ => return self.mismatch("method"),
+        }
+        self
+    }
+
+    pub fn event(mut self, event: Event) -> Self {
+        match &mut self.kind {
+            TypeBuilderKind::Struct { events, .. } => events.push(event),
+            _ => return self.mismatch("event"),
+        }
+        self
+    }
+
+    pub fn build(self) -> Result<Type, BuildError> {
+        check_name("type", &self.name, false)?;
+        if let Some(member) = self.mismatch {
+            return Err(BuildError::Mismatch {
+                member,
+                kind: self.kind.name(),
+                name: self.name,
+            });
+        }
+        let t = match self.kind {
+            TypeBuilderKind::Enum(variants) => {
+                if variants.iter().any(|v| v.value().is_empty()) {
+                    return Err(BuildError::EmptyName {
+                        kind: "enum variant",
+                    });
+                }
+                check_unique(
+                    "enum variant",
+                    &self.name,
+                    variants.iter().map(EnumVariant::value),
+                )?;
+                Type::new_enum(self.name, self.description, variants)
+            }
+            TypeBuilderKind::Data => Type::new_data(self.name, self.description),
+            TypeBuilderKind::Struct {
+                elements,
+                optional_elements,
+                methods,
+                events,
+            } => {
+                check_unique(
+                    "member",
+                    &self.name,
+                    elements
+                        .iter()
+                        .chain(&optional_elements)
+                        .map(Element::name)
+                        .chain(methods.iter().map(Method::name))
+                        .chain(events.iter().map(Event::name)),
+                )?;
+                Type::new_struct(
+                    self.name,
+                    self.description,
+                    elements,
+                    optional_elements,
+                    methods,
+                    events,
+                )
+            }
+        };
+        Ok(t.with_availability(self.availability))
+    }
+}
+
+pub fn element(name: &str, type_ref: TypeRef) -> Result<Element, BuildError> {
+    check_name("element", name, false)?;
+    Element::new(type_ref, name.to_owned(), None).map_err(|_| BuildError::InvalidName {
+        kind: "element",
+        name: name.to_owned(),
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct MethodBuilder {
+    name: String,
+    description: Option<String>,
+    availability: Availability,
+    args: Vec<Argument>,
+    returns: Option<Returns>,
+    supports_promise: bool,
+    permissions: Vec<String>,
+}
+
+impl MethodBuilder {
+    pub fn new(name: &str) -> Self {
+        MethodBuilder {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    pub fn availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    pub fn arg(mut self, element: Element, optional: bool) -> Self {
+        self.args.push(Argument::new_element(element, optional));
+        self
+    }
+
+    pub fn callback(mut self, callback: Method, optional: bool) -> Self {
+        self.args.push(Argument::new_callback(callback, optional));
+        self
+    }
+
+    pub fn returns(mut self, type_ref: TypeRef, description: Option<&str>) -> Self {
+        self.returns = Some(Returns::new(type_ref, description.map(str::to_owned)));
+        self
+    }
+
+    pub fn supports_promise(mut self, supports_promise: bool) -> Self {
+        self.supports_promise = supports_promise;
+        self
+    }
+
+    pub fn permission(mut self, permission: &str) -> Self {
+        self.permissions.push(permission.to_owned());
+        self
+    }
+
+    pub fn build(self) -> Result<Method, BuildError> {
+        check_name("method", &self.name, false)?;
+        check_unique("argument", &self.name, self.args.iter().map(arg_name))?;
+        Ok(Method::new(
+            self.name,
+            self.args,
+            self.returns,
+            self.supports_promise,
+            self.description,
+        )
+        .with_permissions(self.permissions)
+        .with_availability(self.availability))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EventBuilder {
+    name: String,
+    description: Option<String>,
+    availability: Availability,
+    callback: Option<Method>,
+    extra_args: Vec<Argument>,
+}
+
+impl EventBuilder {
+    pub fn new(name: &str) -> Self {
+        EventBuilder {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    pub fn availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    pub fn callback(mut self, callback: Method) -> Self {
+        self.callback = Some(callback);
+        self
+    }
+
+    pub fn extra_arg(mut self, element: Element, optional: bool) -> Self {
+        self.extra_args
+            .push(Argument::new_element(element, optional));
+        self
+    }
+
+    pub fn build(self) -> Result<Event, BuildError> {
+        check_name("event", &self.name, false)?;
+        let callback = match self.callback {
+            Some(callback) => callback,
+            None => MethodBuilder::new("callback").build()?,
+        };
+        check_unique(
+            "argument",
+            &self.name,
+            std::iter::once(callback.name()).chain(self.extra_args.iter().map(arg_name)),
+        )?;
+
+        let mut args = vec![Argument::new_callback(callback, false)];
+        args.extend(self.extra_args);
+        let add_listener = Method::new("addListener".to_owned(), args, None, false, None);
+        Ok(Event::new(self.name, add_listener, self.description)
+            .with_availability(self.availability))
+    }
+}
+
+#[derive(Debug)]
+pub struct PropertyBuilder {
+    name: String,
+    description: Option<String>,
+    availability: Availability,
+    kind: PropertyKind,
+    mismatched_method: bool,
+}
+
+impl PropertyBuilder {
+    pub fn new_immediate(name: &str, type_ref: TypeRef) -> Self {
+        PropertyBuilder {
+            name: name.to_owned(),
+            description: None,
+            availability: Availability::default(),
+            mismatched_method: false,
+            kind: PropertyKind::Immediate {
+                type_ref,
+                value: None,
+            },
+        }
+    }
+
+    pub fn new_constant(name: &str, value: Literal) -> Self {
+        PropertyBuilder {
+            name: name.to_owned(),
+            description: None,
+            availability: Availability::default(),
+            mismatched_method: false,
+            kind: PropertyKind::Immediate {
+                type_ref: value.type_ref(),
+                value: Some(value),
+            },
+        }
+    }
+
+    pub fn new_object(name: &str) -> Self {
+        PropertyBuilder {
+            name: name.to_owned(),
+            description: None,
+            availability: Availability::default(),
+            kind: PropertyKind::Object { methods: vec![] },
+            mismatched_method: false,
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    pub fn availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    // Only fits builders created with `new_object`; anything else fails `build`.
+    pub fn method(mut self, method: Method) -> Self {
+        match &mut self.kind {
+            PropertyKind::Object { methods } => methods.push(method),
+            _ => self.mismatched_method = true,
+        }
+        self
+    }
+
+    pub fn build(self) -> Result<Property, BuildError> {
+        check_name("property", &self.name, false)?;
+        if self.mismatched_method {
+            return Err(BuildError::Mismatch {
+                member: "method",
+                kind: "property",
+                name: self.name,
+            });
+        }
+        let property = match self.kind {
+            PropertyKind::Immediate { type_ref, value } => {
+                Property::new_immediate(self.name, type_ref, value, self.description)
+            }
+            PropertyKind::Object { methods } => {
+                check_unique("method", &self.name, methods.iter().map(Method::name))?;
+                Property::new_object(self.name, methods, self.description)
+            }
+        };
+        Ok(property.with_availability(self.availability))
+    }
+}
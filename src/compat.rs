@@ -0,0 +1,330 @@
+//! Compatibility matrices comparing the same APIs across browsers.
+//!
+//! Namespaces, methods, their parameters, events, their callback parameters and enum
+//! values are matched by dotted path (`tabs.query.queryInfo`, `tabs.TabStatus.loading`).
+//! Each row records, per browser, whether the item exists and a short signature used
+//! to spot differences. Type names are compared as written, so every browser's
+//! namespaces should be linked the same way (or not at all) before comparing.
+
+use crate::api::{Argument, ArgumentKind, Event, Method, Namespace, TypeKind};
+use serde_json::json;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Write};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ItemKind {
+    Namespace,
+    Method,
+    Parameter,
+    Event,
+    EnumValue,
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ItemKind::Namespace => "namespace",
+            ItemKind::Method => "method",
+            ItemKind::Parameter => "parameter",
+            ItemKind::Event => "event",
+            ItemKind::EnumValue => "enum value",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Row {
+    kind: ItemKind,
+    path: String,
+    signatures: Vec<Option<String>>,
+}
+
+impl Row {
+    pub fn kind(&self) -> ItemKind {
+        self.kind
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    // One entry per browser, in the matrix's browser order; `None` where it is missing.
+    // Namespaces and enum values have an empty signature.
+    pub fn signatures(&self) -> &[Option<String>] {
+        &self.signatures
+    }
+
+    pub fn is_missing_anywhere(&self) -> bool {
+        self.signatures.iter().any(Option::is_none)
+    }
+
+    // Whether the browsers that have the item disagree on its signature.
+    pub fn has_signature_differences(&self) -> bool {
+        let mut present = self.signatures.iter().flatten();
+        match present.next() {
+            Some(first) => present.any(|s| s != first),
+            None => false,
+        }
+    }
+
+    pub fn is_compatible(&self) -> bool {
+        !self.is_missing_anywhere() && !self.has_signature_differences()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Matrix {
+    browsers: Vec<String>,
+    rows: Vec<Row>,
+}
+
+impl Matrix {
+    // Takes each browser's name along with its namespaces.
+    pub fn new(browsers: &[(&str, &[Namespace])]) -> Matrix {
+        let mut rows: Vec<Row> = vec![];
+        let mut index: HashMap<(ItemKind, String), usize> = HashMap::new();
+        for (i, (_, namespaces)) in browsers.iter().enumerate() {
+            let mut items = vec![];
+            for namespace in namespaces.iter() {
+                namespace_items(namespace, &mut items);
+            }
+            for (kind, path, signature) in items {
+                let row = *index.entry((kind, path.clone())).or_insert_with(|| {
+                    rows.push(Row {
+                        kind,
+                        path,
+                        signatures: vec![None; browsers.len()],
+                    });
+                    rows.len() - 1
+                });
+                rows[row].signatures[i] = Some(signature);
+            }
+        }
+        // Sorting by segments keeps every item right below its parent.
+        rows.sort_by(|a, b| a.path.split('.').cmp(b.path.split('.')));
+
+        Matrix {
+            browsers: browsers
+                .iter()
+                .map(|(name, _)| (*name).to_owned())
+                .collect(),
+            rows,
+        }
+    }
+
+    pub fn browsers(&self) -> &[String] {
+        &self.browsers
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    // The rows that need a polyfill or a workaround somewhere.
+    pub fn incompatibilities(&self) -> impl Iterator<Item = &Row> {
+        self.rows.iter().filter(|row| !row.is_compatible())
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        let items = self
+            .rows
+            .iter()
+            .map(|row| {
+                let signatures = self
+                    .browsers
+                    .iter()
+                    .zip(&row.signatures)
+                    .map(|(browser, signature)| (browser.clone(), json!(signature)))
+                    .collect::<serde_json::Map<_, _>>();
+                json!({
+                    "path": row.path,
+                    "kind": row.kind.to_string(),
+                    "missing": row.is_missing_anywhere(),
+                    "differs": row.has_signature_differences(),
+                    "signatures": signatures,
+                })
+            })
+            .collect::<Vec<_>>();
+        let matrix = json!({ "browsers": self.browsers, "items": items });
+        Ok(serde_json::to_string_pretty(&matrix)?)
+    }
+
+    // Present items show "yes", or their signature in code when browsers disagree.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("| Item | Kind |");
+        for browser in &self.browsers {
+            let _ = write!(out, " {} |", escape_markdown(browser));
+        }
+        out.push_str("\n| --- | --- |");
+        for _ in &self.browsers {
+            out.push_str(" --- |");
+        }
+        out.push('\n');
+
+        for row in &self.rows {
+            let _ = write!(out, "| `{}` | {} |", row.path, row.kind);
+            for signature in &row.signatures {
+                let cell = match signature {
+                    None => "—".to_owned(),
+                    Some(s) if row.has_signature_differences() => {
+                        format!("`{}`", escape_markdown(s))
+                    }
+                    Some(_) => "yes".to_owned(),
+                };
+                let _ = write!(out, " {} |", cell);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // A bare `<table>`; rows carry `missing` and `differs` classes for styling.
+    pub fn to_html(&self) -> String {
+        let mut out =
+            String::from("<table class=\"compat\">\n<thead>\n<tr><th>Item</th><th>Kind</th>");
+        for browser in &self.browsers {
+            let _ = write!(out, "<th>{}</th>", escape_html(browser));
+        }
+        out.push_str("</tr>\n</thead>\n<tbody>\n");
+
+        for row in &self.rows {
+            let mut classes = vec![];
+            if row.is_missing_anywhere() {
+                classes.push("missing");
+            }
+            if row.has_signature_differences() {
+                classes.push("differs");
+            }
+            if classes.is_empty() {
+                out.push_str("<tr>");
+            } else {
+                let _ = write!(out, "<tr class=\"{}\">", classes.join(" "));
+            }
+            let _ = write!(
+                out,
+                "<td><code>{}</code></td><td>{}</td>",
+                escape_html(&row.path),
+                row.kind
+            );
+            for signature in &row.signatures {
+                match signature {
+                    None => out.push_str("<td class=\"absent\">—</td>"),
+                    Some(s) if row.has_signature_differences() => {
+                        let _ = write!(out, "<td><code>{}</code></td>", escape_html(s));
+                    }
+                    Some(_) => out.push_str("<td>yes</td>"),
+                }
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</tbody>\n</table>\n");
+        out
+    }
+}
+
+type Item = (ItemKind, String, String);
+
+fn namespace_items(namespace: &Namespace, items: &mut Vec<Item>) {
+    let name = namespace.name();
+    items.push((ItemKind::Namespace, name.to_owned(), String::new()));
+
+    for t in namespace.types() {
+        if let TypeKind::Enum { variants } = t.kind() {
+            for variant in variants {
+                let path = format!("{}.{}.{}", name, t.name(), variant.value());
+                items.push((ItemKind::EnumValue, path, String::new()));
+            }
+        }
+    }
+    for method in namespace.methods() {
+        let path = format!("{}.{}", name, method.name());
+        items.push((ItemKind::Method, path.clone(), method_signature(method)));
+        parameter_items(&path, method.args(), items);
+    }
+    for event in namespace.events() {
+        let path = format!("{}.{}", name, event.name());
+        items.push((ItemKind::Event, path.clone(), event_signature(event)));
+        parameter_items(&path, event.callback().args(), items);
+    }
+}
+
+fn parameter_items(parent: &str, args: &[Argument], items: &mut Vec<Item>) {
+    for arg in args {
+        let path = format!("{}.{}", parent, argument_name(arg));
+        let signature = if arg.is_optional() {
+            format!("optional {}", argument_type(arg))
+        } else {
+            argument_type(arg)
+        };
+        items.push((ItemKind::Parameter, path, signature));
+    }
+}
+
+// Parameter types have rows of their own, so a method is summed up by its parameter
+// names and what it returns, e.g. `(tabId, callback?) -> Promise<Tab>`.
+fn method_signature(method: &Method) -> String {
+    let mut signature = format!("({})", parameter_names(method.args()));
+    if method.supports_promise() {
+        let resolved = method
+            .promise_type()
+            .map_or_else(|| "void".to_owned(), |t| t.to_string());
+        let _ = write!(signature, " -> Promise<{}>", resolved);
+    } else if let Some(returns) = method.returns() {
+        let _ = write!(signature, " -> {}", returns.type_ref());
+    }
+    signature
+}
+
+fn event_signature(event: &Event) -> String {
+    let mut signature = format!("({})", parameter_names(event.callback().args()));
+    if !event.extra_args().is_empty() {
+        let _ = write!(signature, " with {}", parameter_names(event.extra_args()));
+    }
+    signature
+}
+
+fn parameter_names(args: &[Argument]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_optional() {
+                format!("{}?", argument_name(arg))
+            } else {
+                argument_name(arg).to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn argument_name(arg: &Argument) -> &str {
+    match arg.kind() {
+        ArgumentKind::Element { element } => element.name(),
+        ArgumentKind::Callback { callback } => callback.name(),
+    }
+}
+
+fn argument_type(arg: &Argument) -> String {
+    match arg.kind() {
+        ArgumentKind::Element { element } => element.type_ref().to_string(),
+        ArgumentKind::Callback { callback } => {
+            let params = callback
+                .args()
+                .iter()
+                .map(|arg| format!("{}: {}", argument_name(arg), argument_type(arg)))
+                .collect::<Vec<_>>();
+            format!("function({})", params.join(", "))
+        }
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,178 @@
+use scraper::ElementRef;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseErrorKind {
+    UnknownSection(String),
+    UnsupportedType(String),
+    InvalidConstant(String),
+    InvalidName(String),
+    Missing(&'static str),
+    Multiple(&'static str),
+    InvalidStructure(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnknownSection(id) => write!(f, "unknown section {:?}", id),
+            ParseErrorKind::UnsupportedType(name) => write!(f, "unsupported type {:?}", name),
+            ParseErrorKind::InvalidConstant(text) => write!(f, "invalid constant {:?}", text),
+            ParseErrorKind::InvalidName(name) => write!(f, "invalid name {:?}", name),
+            ParseErrorKind::Missing(what) => write!(f, "no {} found", what),
+            ParseErrorKind::Multiple(what) => write!(f, "multiple {} found", what),
+            ParseErrorKind::InvalidStructure(message) => f.write_str(message),
+        }
+    }
+}
+
+// Errors are created where parsing fails with only the kind and the offending node;
+// the enclosing item, section and namespace are filled in on the way up.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError(Box<Context>);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Context {
+    kind: ParseErrorKind,
+    namespace: Option<String>,
+    section: Option<String>,
+    item: Option<String>,
+    node: Option<String>,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind) -> Self {
+        ParseError(Box::new(Context {
+            kind,
+            namespace: None,
+            section: None,
+            item: None,
+            node: None,
+        }))
+    }
+
+    pub(crate) fn structure(message: impl Into<String>) -> Self {
+        ParseError::new(ParseErrorKind::InvalidStructure(message.into()))
+    }
+
+    pub(crate) fn at(mut self, node: ElementRef) -> Self {
+        self.0.node.get_or_insert_with(|| node_path(node));
+        self
+    }
+
+    pub(crate) fn in_item(mut self, item: &str) -> Self {
+        self.0.item.get_or_insert_with(|| item.to_owned());
+        self
+    }
+
+    pub(crate) fn in_section(mut self, section: &str) -> Self {
+        self.0.section.get_or_insert_with(|| section.to_owned());
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace: &str) -> Self {
+        self.0.namespace.get_or_insert_with(|| namespace.to_owned());
+        self
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.0.kind
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.0.namespace.as_deref()
+    }
+
+    pub fn section(&self) -> Option<&str> {
+        self.0.section.as_deref()
+    }
+
+    pub fn item(&self) -> Option<&str> {
+        self.0.item.as_deref()
+    }
+
+    // A CSS-like path to the HTML node that failed, e.g.
+    // `div.api-reference > div > table > tbody > tr#property-get-tabId`.
+    pub fn node(&self) -> Option<&str> {
+        self.0.node.as_deref()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let location = [&self.0.namespace, &self.0.section, &self.0.item]
+            .iter()
+            .filter_map(|s| s.as_deref())
+            .collect::<Vec<_>>();
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(" / "))?;
+        }
+        write!(f, "{}", self.0.kind)?;
+        if let Some(node) = &self.0.node {
+            write!(f, " (at {})", node)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn node_path(node: ElementRef) -> String {
+    let mut segments = vec![];
+    let mut current = Some(node);
+    while let Some(e) = current {
+        let element = e.value();
+        if ["html", "body"].contains(&element.name()) {
+            break;
+        }
+        let mut segment = element.name().to_owned();
+        if let Some(id) = element.id() {
+            segment.push('#');
+            segment.push_str(id);
+        } else if let Some(class) = element.classes().next() {
+            segment.push('.');
+            segment.push_str(class);
+        }
+        segments.push(segment);
+        current = e.parent().and_then(ElementRef::wrap);
+    }
+    segments.reverse();
+    segments.join(" > ")
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    // Something was parsed, but with less detail than the page has.
+    Warning,
+    // An item was skipped entirely.
+    Error,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    severity: Severity,
+    error: ParseError,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(severity: Severity, error: ParseError) -> Self {
+        Diagnostic { severity, error }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn error(&self) -> &ParseError {
+        &self.error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.error),
+            Severity::Error => write!(f, "error: {}", self.error),
+        }
+    }
+}
@@ -0,0 +1,720 @@
+use crate::error::{ParseError, ParseErrorKind, Severity};
+use crate::{api, code_spans, markdown, parse_availability, parse_literal, util, Reporter};
+use scraper::{ElementRef, Html, Selector};
+
+pub(crate) const INDEX_URL: &str = "https://developer.chrome.com/docs/extensions/reference/api";
+
+// Reference pages are a flat run of headings and paragraphs: an `h2` per section, an
+// `h3` per item and an `h4` per part of an item ("Properties", "Parameters", ...).
+// Properties and parameters are `ul` lists whose entries nest further lists for
+// object fields and callback parameters. Methods and events of a type are listed
+// under its "Methods" and "Events" parts, with an `h5` per member and an `h6` per part.
+const API_SECTIONS: &str = "h2#type, h2#property, h2#method, h2#event";
+
+pub(crate) fn is_layout(api_root: &Html) -> bool {
+    api_root
+        .select(&Selector::parse(API_SECTIONS).unwrap())
+        .next()
+        .is_some()
+}
+
+pub(crate) fn parse_index(api_root: &Html) -> Vec<(String, String)> {
+    const ORIGIN: &str = "https://developer.chrome.com";
+    const PREFIX: &str = "/docs/extensions/reference/api/";
+
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let mut pages: Vec<(String, String)> = vec![];
+    for href in api_root
+        .select(&link_selector)
+        .filter_map(|link| link.value().attr("href"))
+    {
+        let path = href.strip_prefix(ORIGIN).unwrap_or(href);
+        let space = match path.strip_prefix(PREFIX) {
+            Some(space) => space
+                .split(['#', '?'])
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('/'),
+            None => continue,
+        };
+        // Nested namespaces live in subdirectories, as in "devtools/inspectedWindow".
+        let name = space.replace('/', ".");
+        if !name.is_empty() && !pages.iter().any(|(n, _)| *n == name) {
+            pages.push((name, format!("{}{}{}", ORIGIN, PREFIX, space)));
+        }
+    }
+    pages
+}
+
+pub(crate) fn parse_namespace(
+    name: &str,
+    api_root: &Html,
+    reporter: &mut Reporter,
+) -> Result<api::Namespace, ParseError> {
+    let container = api_root
+        .select(&Selector::parse(API_SECTIONS).unwrap())
+        .next()
+        .and_then(|h2| h2.parent())
+        .and_then(ElementRef::wrap)
+        .ok_or_else(|| {
+            ParseError::new(ParseErrorKind::Missing("API reference")).in_namespace(name)
+        })?;
+    let children = container
+        .children()
+        .filter_map(ElementRef::wrap)
+        .collect::<Vec<_>>();
+
+    let mut description = None;
+    let mut availability = vec![];
+    let mut permissions = vec![];
+    let mut optional_permissions = vec![];
+    let mut manifest_keys = vec![];
+    let mut types = vec![];
+    let mut properties = vec![];
+    let mut methods = vec![];
+    let mut events = vec![];
+
+    for (title, body) in split_at(&children, "h2").1 {
+        let section = title.value().id().unwrap_or_default();
+        match section {
+            "description" => description = paragraphs(&body),
+            "availability" => availability = body.iter().flat_map(|e| badges(*e)).collect(),
+            "permissions" => {
+                // Permission names are paragraphs of nothing but code spans; the prose
+                // around them explains when they are needed, and names those that can
+                // be requested as optional the way legacy pages do.
+                for markdown in body.iter().filter_map(|e| markdown::from_element(*e)) {
+                    let prose = markdown.split('`').step_by(2).collect::<String>();
+                    let (list, names) = if prose
+                        .trim_matches(|c: char| c == ',' || c.is_whitespace())
+                        .is_empty()
+                    {
+                        let names = code_spans(&markdown)
+                            .map(|code| code.trim().trim_matches('"').to_owned())
+                            .filter(|code| !code.is_empty())
+                            .collect();
+                        (&mut permissions, names)
+                    } else {
+                        let names = markdown
+                            .split(". ")
+                            .filter(|sentence| sentence.contains("optional"))
+                            .flat_map(crate::permission_names)
+                            .collect::<Vec<_>>();
+                        (&mut optional_permissions, names)
+                    };
+                    for name in names {
+                        if !list.contains(&name) {
+                            list.push(name);
+                        }
+                    }
+                }
+            }
+            "manifest" => {
+                manifest_keys = body
+                    .iter()
+                    .filter_map(|e| markdown::from_element(*e))
+                    .flat_map(|markdown| {
+                        code_spans(&markdown)
+                            .filter_map(|code| code.split('"').nth(1))
+                            .map(str::to_owned)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
+            }
+            "type" | "property" | "method" | "event" => {
+                reporter.section = section.to_owned();
+                for (title, body) in split_at(&body, "h3").1 {
+                    let item = Item::new(title, &body, "h4");
+                    let parsed = match section {
+                        "type" => parse_type(&item).map(|t| types.push(t)),
+                        "property" => parse_property(&item).map(|p| properties.push(p)),
+                        "method" => parse_method(&item).map(|m| methods.push(m)),
+                        _ => parse_event(&item).map(|e| events.push(e)),
+                    };
+                    // A broken item shouldn't hide the rest of the page, so it is skipped.
+                    if let Err(e) = parsed {
+                        reporter.report(Severity::Error, e.in_item(&item.name))?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let availability = availability_from(&availability, &description);
+    Ok(api::Namespace::new(
+        name.to_owned(),
+        description,
+        types,
+        properties,
+        methods,
+        events,
+    )
+    .with_availability(availability)
+    .with_permissions(permissions, optional_permissions)
+    .with_manifest_keys(manifest_keys))
+}
+
+type Section<'a> = (ElementRef<'a>, Vec<ElementRef<'a>>);
+
+// Splits siblings into the elements before the first `tag` heading and the runs of
+// elements that follow each heading.
+fn split_at<'a>(elements: &[ElementRef<'a>], tag: &str) -> (Vec<ElementRef<'a>>, Vec<Section<'a>>) {
+    let mut preamble = vec![];
+    let mut sections: Vec<Section<'a>> = vec![];
+    for &e in elements {
+        if e.value().name() == tag {
+            sections.push((e, vec![]));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push(e);
+        } else {
+            preamble.push(e);
+        }
+    }
+    (preamble, sections)
+}
+
+fn children_named<'a>(e: ElementRef<'a>, name: &'static str) -> Vec<ElementRef<'a>> {
+    e.children()
+        .filter_map(ElementRef::wrap)
+        .filter(|c| c.value().name() == name)
+        .collect()
+}
+
+fn text_of(e: ElementRef) -> String {
+    e.text().collect::<Vec<_>>().join(" ")
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Badges such as "Chrome 46+", "Promise" or "ChromeOS only" are paragraphs made up of
+// nothing but `span`s.
+fn badges(e: ElementRef) -> Vec<String> {
+    let spans = children_named(e, "span");
+    let only_spans = !spans.is_empty()
+        && e.children().all(|node| {
+            ElementRef::wrap(node).map_or_else(
+                || node.value().as_text().is_none_or(|t| t.trim().is_empty()),
+                |c| c.value().name() == "span",
+            )
+        });
+    if !only_spans {
+        return vec![];
+    }
+    spans.into_iter().map(|s| normalize(&text_of(s))).collect()
+}
+
+fn is_badge_line(e: ElementRef) -> bool {
+    !badges(e).is_empty()
+}
+
+// Rewrites badges into the sentences that the legacy pages used, so that both layouts
+// share `parse_availability`.
+fn availability_from(badges: &[String], description: &Option<String>) -> api::Availability {
+    let mut text = badges
+        .iter()
+        .filter_map(|badge| {
+            if let Some(version) = badge
+                .strip_prefix("Chrome ")
+                .and_then(|v| v.strip_suffix('+'))
+            {
+                return Some(format!("Since Chrome {}.", version));
+            }
+            match badge.as_str() {
+                "Beta" => Some("Beta channel.".to_owned()),
+                "Dev" => Some("Dev channel.".to_owned()),
+                "Canary" => Some("Canary channel.".to_owned()),
+                _ if badge.starts_with("Deprecated") || badge.ends_with(" only") => {
+                    Some(format!("{}.", badge.trim_end_matches('.')))
+                }
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    text.extend(description.clone());
+    parse_availability(&text.join("\n\n"))
+}
+
+fn paragraphs(elements: &[ElementRef]) -> Option<String> {
+    let paragraphs = elements
+        .iter()
+        .filter(|e| e.value().name() == "p" && !is_badge_line(**e))
+        .filter_map(|e| markdown::from_element(*e))
+        .collect::<Vec<_>>();
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("\n\n"))
+    }
+}
+
+struct Item<'a> {
+    title: ElementRef<'a>,
+    name: String,
+    description: Option<String>,
+    availability: api::Availability,
+    signature: String,
+    promise: bool,
+    parts: Vec<Section<'a>>,
+}
+
+impl<'a> Item<'a> {
+    // Members of a type have ids such as `method-StorageArea-get`.
+    fn new(title: ElementRef<'a>, body: &[ElementRef<'a>], part_tag: &str) -> Self {
+        let name = match title.value().id().and_then(|id| id.rsplit_once('-')) {
+            Some((_, name)) => name.to_owned(),
+            None => normalize(&text_of(title)).trim_end_matches("()").to_owned(),
+        };
+        let (preamble, parts) = split_at(body, part_tag);
+        let badges = preamble.iter().flat_map(|e| badges(*e)).collect::<Vec<_>>();
+        let signature = preamble
+            .iter()
+            .filter(|e| e.value().name() == "pre")
+            .map(|e| text_of(*e))
+            .collect::<String>();
+        let description = paragraphs(&preamble);
+
+        Item {
+            title,
+            name,
+            availability: availability_from(&badges, &description),
+            description,
+            promise: badges.iter().any(|b| b == "Promise"),
+            signature,
+            parts,
+        }
+    }
+
+    fn part(&self, heading: &str) -> Option<&[ElementRef<'a>]> {
+        self.parts
+            .iter()
+            .find(|(h4, _)| normalize(&text_of(*h4)) == heading)
+            .map(|(_, body)| body.as_slice())
+    }
+
+    fn list(&self, heading: &str) -> Option<ElementRef<'a>> {
+        self.part(heading)?
+            .iter()
+            .copied()
+            .find(|e| e.value().name() == "ul")
+    }
+
+    fn members<T>(
+        &self,
+        heading: &str,
+        parse: impl Fn(&Item) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let body = match self.part(heading) {
+            Some(body) => body,
+            None => return Ok(vec![]),
+        };
+        split_at(body, "h5")
+            .1
+            .into_iter()
+            .map(|(title, body)| {
+                let member = Item::new(title, &body, "h6");
+                parse(&member).map_err(|e| e.in_item(&format!("{}.{}", self.name, member.name)))
+            })
+            .collect()
+    }
+}
+
+struct Entry<'a> {
+    li: ElementRef<'a>,
+    name: String,
+    type_name: String,
+    optional: bool,
+    description: Option<String>,
+    availability: api::Availability,
+    nested: Option<ElementRef<'a>>,
+}
+
+// An entry is `<li><p><code>name</code></p><p>type optional</p><p>...</p><ul>...</ul></li>`.
+fn parse_entry(li: ElementRef) -> Result<Entry, ParseError> {
+    let ps = children_named(li, "p");
+    let mut lines = ps.iter().copied().filter(|p| !is_badge_line(*p));
+    let name = lines
+        .next()
+        .map(|p| {
+            let code_selector = Selector::parse("code").unwrap();
+            p.select(&code_selector)
+                .next()
+                .map_or_else(|| text_of(p), text_of)
+        })
+        .map(|name| normalize(&name))
+        .ok_or_else(|| ParseError::new(ParseErrorKind::Missing("entry name")).at(li))?;
+    let type_name = lines
+        .next()
+        .map(|p| normalize(&text_of(p)))
+        .ok_or_else(|| ParseError::new(ParseErrorKind::Missing("entry type")).at(li))?;
+    let (type_name, optional) = match type_name.strip_suffix("optional") {
+        Some(type_name) => (type_name.trim().to_owned(), true),
+        None => (type_name, false),
+    };
+    let description = paragraphs(&lines.collect::<Vec<_>>());
+    let badges = ps.iter().flat_map(|p| badges(*p)).collect::<Vec<_>>();
+
+    Ok(Entry {
+        li,
+        name,
+        type_name,
+        optional,
+        availability: availability_from(&badges, &description),
+        description,
+        nested: children_named(li, "ul").into_iter().next(),
+    })
+}
+
+fn parse_entries(ul: ElementRef) -> Result<Vec<Entry>, ParseError> {
+    children_named(ul, "li")
+        .into_iter()
+        .map(parse_entry)
+        .collect()
+}
+
+fn parse_element(entry: Entry, parent: &str) -> Result<api::Element, ParseError> {
+    let li = entry.li;
+    let mut type_ref = parse_ts_type(&entry.type_name).map_err(|e| e.at(li))?;
+
+    if let Some(ul) = entry.nested {
+        if let Some(object) = type_ref.inline_object_mut() {
+            let name = format!("{}{}", parent, util::pascal_case(&entry.name));
+            let (elements, optional_elements) = parse_fields(ul, &name)?;
+            *object = api::TypeRef::Object(Some(Box::new(api::Type::new_struct(
+                name,
+                None,
+                elements,
+                optional_elements,
+                vec![],
+                vec![],
+            ))));
+        }
+    }
+
+    Ok(api::Element::new(type_ref, entry.name, entry.description)
+        .map_err(|e| e.at(li))?
+        .with_availability(entry.availability))
+}
+
+fn parse_fields(
+    ul: ElementRef,
+    parent: &str,
+) -> Result<(Vec<api::Element>, Vec<api::Element>), ParseError> {
+    let mut elements = vec![];
+    let mut optional_elements = vec![];
+    for entry in parse_entries(ul)? {
+        if entry.optional {
+            optional_elements.push(parse_element(entry, parent)?);
+        } else {
+            elements.push(parse_element(entry, parent)?);
+        }
+    }
+    Ok((elements, optional_elements))
+}
+
+fn parse_arguments(ul: ElementRef) -> Result<Vec<api::Argument>, ParseError> {
+    let mut args = vec![];
+    for entry in parse_entries(ul)? {
+        let optional = entry.optional;
+        let arg = if entry.type_name == "function" {
+            let callback_args = match entry.nested {
+                Some(ul) => parse_arguments(ul)?,
+                None => vec![],
+            };
+            let callback =
+                api::Method::new(entry.name, callback_args, None, false, entry.description)
+                    .with_availability(entry.availability);
+            api::Argument::new_callback(callback, optional)
+        } else {
+            api::Argument::new_element(parse_element(entry, "")?, optional)
+        };
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+fn parse_type(item: &Item) -> Result<api::Type, ParseError> {
+    let t = if let Some(body) = item.part("Enum") {
+        api::Type::new_enum(
+            item.name.clone(),
+            item.description.clone(),
+            parse_enum_variants(body),
+        )
+    } else if ["Properties", "Methods", "Events"]
+        .iter()
+        .any(|heading| item.part(heading).is_some())
+    {
+        let (elements, optional_elements) = match item.list("Properties") {
+            Some(ul) => parse_fields(ul, &item.name)?,
+            None => (vec![], vec![]),
+        };
+        api::Type::new_struct(
+            item.name.clone(),
+            item.description.clone(),
+            elements,
+            optional_elements,
+            item.members("Methods", parse_method)?,
+            item.members("Events", parse_event)?,
+        )
+    } else {
+        api::Type::new_data(item.name.clone(), item.description.clone())
+    };
+    Ok(t.with_availability(item.availability.clone()))
+}
+
+// Values are either a `dl` of quoted values and their descriptions, or paragraphs of
+// quoted values, each optionally followed by a paragraph describing it.
+fn parse_enum_variants(body: &[ElementRef]) -> Vec<api::EnumVariant> {
+    let mut variants: Vec<(String, Option<String>)> = vec![];
+    for &e in body {
+        match e.value().name() {
+            "dl" => {
+                for dt in children_named(e, "dt") {
+                    let description = dt
+                        .next_siblings()
+                        .filter_map(ElementRef::wrap)
+                        .next()
+                        .filter(|dd| dd.value().name() == "dd")
+                        .and_then(markdown::from_element);
+                    let value = normalize(&text_of(dt)).trim_matches('"').to_owned();
+                    variants.push((value, description));
+                }
+            }
+            "p" => {
+                let text = normalize(&text_of(e));
+                if text.starts_with('"') {
+                    variants.extend(
+                        text.split('"')
+                            .skip(1)
+                            .step_by(2)
+                            .map(|value| (value.to_owned(), None)),
+                    );
+                } else if let Some((_, description)) = variants.last_mut() {
+                    *description = markdown::from_element(e);
+                }
+            }
+            _ => {}
+        }
+    }
+    variants
+        .into_iter()
+        .map(|(value, description)| api::EnumVariant::new(value, description))
+        .collect()
+}
+
+fn parse_property(item: &Item) -> Result<api::Property, ParseError> {
+    let property = if let Some(body) = item.part("Value") {
+        let text = body
+            .first()
+            .map(|e| normalize(&text_of(*e)))
+            .unwrap_or_default();
+        let value = parse_literal(&text).ok_or_else(|| {
+            ParseError::new(ParseErrorKind::InvalidConstant(text.clone())).at(item.title)
+        })?;
+        api::Property::new_immediate(
+            item.name.clone(),
+            value.type_ref(),
+            Some(value),
+            item.description.clone(),
+        )
+    } else if let Some(body) = item.part("Type") {
+        let text = body
+            .first()
+            .map(|e| normalize(&text_of(*e)))
+            .unwrap_or_default();
+        let mut type_ref = parse_ts_type(&text).map_err(|e| e.at(item.title))?;
+        if let (Some(ul), Some(object)) = (item.list("Properties"), type_ref.inline_object_mut()) {
+            let name = util::pascal_case(&item.name);
+            let (elements, optional_elements) = parse_fields(ul, &name)?;
+            *object = api::TypeRef::Object(Some(Box::new(api::Type::new_struct(
+                name,
+                None,
+                elements,
+                optional_elements,
+                vec![],
+                vec![],
+            ))));
+        }
+        api::Property::new_immediate(item.name.clone(), type_ref, None, item.description.clone())
+    } else {
+        return Err(ParseError::new(ParseErrorKind::Missing("property type")).at(item.title));
+    };
+    Ok(property.with_availability(item.availability.clone()))
+}
+
+fn parse_method(item: &Item) -> Result<api::Method, ParseError> {
+    let mut args = match item.list("Parameters") {
+        Some(ul) => parse_arguments(ul)?,
+        None => vec![],
+    };
+
+    let mut supports_promise = item.promise || item.signature.contains("Promise<");
+    let mut returns = None;
+    if let Some(body) = item.part("Returns") {
+        // Either a bare type paragraph or a one-entry list without a name.
+        let lines = match body.first() {
+            Some(ul) if ul.value().name() == "ul" => children_named(*ul, "li")
+                .into_iter()
+                .next()
+                .map(|li| children_named(li, "p"))
+                .unwrap_or_default(),
+            _ => body.to_vec(),
+        };
+        let type_name = lines
+            .first()
+            .map(|p| normalize(&text_of(*p)))
+            .ok_or_else(|| {
+                ParseError::new(ParseErrorKind::Missing("return type")).at(item.title)
+            })?;
+        match type_name
+            .strip_prefix("Promise<")
+            .and_then(|t| t.strip_suffix('>'))
+        {
+            // The resolved value is the same as the callback's parameters. Methods that
+            // only return a promise get the callback they would have taken.
+            Some(resolved) => {
+                supports_promise = true;
+                let has_callback = args
+                    .iter()
+                    .any(|a| matches!(a.kind(), api::ArgumentKind::Callback { .. }));
+                if !has_callback {
+                    let callback_args = match resolved {
+                        "void" => vec![],
+                        resolved => {
+                            let type_ref = parse_ts_type(resolved).map_err(|e| e.at(item.title))?;
+                            let element = api::Element::new(type_ref, "result".to_owned(), None)
+                                .map_err(|e| e.at(item.title))?;
+                            vec![api::Argument::new_element(element, false)]
+                        }
+                    };
+                    let callback =
+                        api::Method::new("callback".to_owned(), callback_args, None, false, None);
+                    args.push(api::Argument::new_callback(callback, true));
+                }
+            }
+            None if type_name != "void" => {
+                let type_ref = parse_ts_type(&type_name).map_err(|e| e.at(item.title))?;
+                returns = Some(api::Returns::new(type_ref, paragraphs(&lines[1..])));
+            }
+            None => {}
+        }
+    }
+
+    let permissions = item
+        .description
+        .as_deref()
+        .map(|d| {
+            d.split(". ")
+                .filter(|sentence| sentence.contains("permission"))
+                .flat_map(crate::permission_names)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(api::Method::new(
+        item.name.clone(),
+        args,
+        returns,
+        supports_promise,
+        item.description.clone(),
+    )
+    .with_availability(item.availability.clone())
+    .with_permissions(permissions))
+}
+
+fn parse_event(item: &Item) -> Result<api::Event, ParseError> {
+    let args = match item.list("Parameters") {
+        Some(ul) => parse_arguments(ul)?,
+        None => vec![],
+    };
+    let add_listener = api::Method::new("addListener".to_owned(), args, None, false, None);
+    Ok(
+        api::Event::new(item.name.clone(), add_listener, item.description.clone())
+            .with_availability(item.availability.clone()),
+    )
+}
+
+// Types are written in TypeScript syntax: `number`, `Tab[]`, `string | string[]` or
+// `"normal" | "popup"`.
+fn parse_ts_type(text: &str) -> Result<api::TypeRef, ParseError> {
+    let text = text.trim();
+    let alternatives = split_alternatives(text);
+    if alternatives.len() > 1 {
+        if let Some(values) = alternatives
+            .iter()
+            .map(|a| string_literal(a))
+            .collect::<Option<Vec<_>>>()
+        {
+            return Ok(api::TypeRef::Enum(values));
+        }
+        return alternatives
+            .iter()
+            .map(|a| parse_ts_type(a))
+            .collect::<Result<_, _>>()
+            .map(api::TypeRef::union);
+    }
+
+    if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        return parse_ts_type(inner);
+    }
+    if let Some(item) = text.strip_suffix("[]") {
+        return Ok(api::TypeRef::Array(Box::new(parse_ts_type(item)?)));
+    }
+    if let Some(value) = string_literal(text) {
+        return Ok(api::TypeRef::Enum(vec![value]));
+    }
+
+    match text {
+        "number" => Ok(api::TypeRef::Primitive(api::Primitive::Number)),
+        "integer" => Ok(api::TypeRef::Primitive(api::Primitive::Integer)),
+        "boolean" => Ok(api::TypeRef::Primitive(api::Primitive::Boolean)),
+        "string" => Ok(api::TypeRef::Primitive(api::Primitive::String)),
+        "object" => Ok(api::TypeRef::Object(None)),
+        "function" => Ok(api::TypeRef::Function),
+        "any" => Ok(api::TypeRef::Any),
+        "ArrayBuffer" => Ok(api::TypeRef::Binary),
+        _ if text.starts_with('{') => Ok(api::TypeRef::Object(None)),
+        _ if !text.is_empty()
+            && text
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') =>
+        {
+            Ok(api::TypeRef::Named(text.to_owned()))
+        }
+        _ => Err(ParseError::new(ParseErrorKind::UnsupportedType(
+            text.to_owned(),
+        ))),
+    }
+}
+
+fn split_alternatives(text: &str) -> Vec<&str> {
+    let mut alternatives = vec![];
+    let mut depth = 0i32;
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' | '<' | '[' | '{' if !quoted => depth += 1,
+            ')' | '>' | ']' | '}' if !quoted => depth -= 1,
+            '|' if !quoted && depth == 0 => {
+                alternatives.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(text[start..].trim());
+    alternatives
+}
+
+fn string_literal(text: &str) -> Option<String> {
+    let value = text.strip_prefix('"')?.strip_suffix('"')?;
+    if value.contains('"') {
+        None
+    } else {
+        Some(value.to_owned())
+    }
+}
@@ -0,0 +1,794 @@
+use crate::error::{ParseError, ParseErrorKind, Severity};
+use crate::{
+    api, availability_of, code_spans, markdown, parse_availability, parse_literal,
+    permission_names, util, Reporter,
+};
+use scraper::{ElementRef, Html, Selector};
+use std::convert::TryFrom;
+
+pub(crate) const INDEX_URL: &str = "https://developer.chrome.com/extensions/api_index";
+
+pub(crate) fn is_layout(api_root: &Html) -> bool {
+    api_root
+        .select(&Selector::parse("div.api-reference").unwrap())
+        .next()
+        .is_some()
+}
+
+pub(crate) fn parse_index(api_root: &Html) -> Vec<(String, String)> {
+    const BASE: &str = "https://developer.chrome.com/extensions/";
+
+    let stable_api_selector =
+        Selector::parse("#stable_apis ~ table:nth-of-type(1) tr td:nth-of-type(1) a").unwrap();
+
+    api_root
+        .select(&stable_api_selector)
+        .filter_map(|link| link.value().attr("href"))
+        // Pages of nested namespaces are named like "devtools_inspectedWindow".
+        .map(|space| (space.replace('_', "."), format!("{}{}", BASE, space)))
+        .collect()
+}
+
+pub(crate) fn parse_namespace(
+    name: &str,
+    api_root: &Html,
+    reporter: &mut Reporter,
+) -> Result<api::Namespace, ParseError> {
+    let api_selector = Selector::parse("div.api-reference > *").unwrap();
+    let api_info = api_root.select(&api_selector).collect::<Vec<_>>();
+    let mut index = 0;
+    let mut types = vec![];
+    let mut methods = vec![];
+    let mut events = vec![];
+    let mut properties = vec![];
+
+    while index < api_info.len() {
+        let title = api_info[index];
+        let section = title.value().id().ok_or_else(|| {
+            ParseError::new(ParseErrorKind::Missing("section id"))
+                .at(title)
+                .in_namespace(name)
+        })?;
+        let api_type =
+            api::ApiType::try_from(section).map_err(|e| e.at(title).in_namespace(name))?;
+        reporter.section = section.to_owned();
+        index += 1;
+        while index < api_info.len() && api_info[index].value().name() != "h2" {
+            let item = api_info[index];
+            let parsed = match api_type {
+                api::ApiType::Types => parse_type(item).map(|t| types.push(t)),
+                api::ApiType::Methods => parse_method(item, "h3").map(|m| methods.push(m)),
+                api::ApiType::Events => parse_event(item).map(|e| events.push(e)),
+                api::ApiType::Properties => {
+                    parse_properties(item, reporter).map(|ps| properties = ps)
+                }
+            };
+            // A broken item shouldn't hide the rest of the page, so it is skipped.
+            if let Err(e) = parsed {
+                let e = match item_name(item) {
+                    Some(item_name) => e.in_item(&item_name),
+                    None => e,
+                };
+                reporter.report(Severity::Error, e)?;
+            }
+            index += 1;
+        }
+    }
+
+    let description = parse_intro(api_root, "Description");
+    let availability = parse_availability(
+        [parse_intro(api_root, "Availability"), description.clone()]
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+            .as_str(),
+    );
+
+    // Each permission is classified by its own sentence: pages such as tabs say that most
+    // of the API works "without declaring any permission" before naming the one it needs.
+    let (permissions, optional_permissions) = match parse_intro(api_root, "Permissions") {
+        Some(text) => {
+            let mut permissions = vec![];
+            let mut optional_permissions = vec![];
+            for sentence in text.split(". ") {
+                let list = if sentence.contains("optional") {
+                    &mut optional_permissions
+                } else {
+                    &mut permissions
+                };
+                for name in permission_names(sentence) {
+                    if !list.contains(&name) {
+                        list.push(name);
+                    }
+                }
+            }
+            (permissions, optional_permissions)
+        }
+        None => (vec![], vec![]),
+    };
+    let manifest_keys = parse_intro(api_root, "Manifest")
+        .map(|text| {
+            code_spans(&text)
+                .filter_map(|code| code.split('"').nth(1))
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(api::Namespace::new(
+        name.to_owned(),
+        description,
+        types,
+        properties,
+        methods,
+        events,
+    )
+    .with_availability(availability)
+    .with_permissions(permissions, optional_permissions)
+    .with_manifest_keys(manifest_keys))
+}
+
+fn item_name(item: scraper::ElementRef) -> Option<String> {
+    let title_selector = Selector::parse("h3").unwrap();
+    item.select(&title_selector)
+        .next()
+        .map(|title| title.text().collect::<String>().trim().to_owned())
+}
+
+fn parse_intro(api_root: &Html, key: &str) -> Option<String> {
+    let row_selector = Selector::parse("table.intro tr").unwrap();
+    api_root.select(&row_selector).find_map(|tr| {
+        let tds = tr
+            .children()
+            .filter_map(ElementRef::wrap)
+            .collect::<Vec<_>>();
+        if tds.len() == 2 && tds[0].text().collect::<String>().trim().starts_with(key) {
+            markdown::from_element(tds[1])
+        } else {
+            None
+        }
+    })
+}
+
+struct ParsedElem<'a> {
+    row: scraper::ElementRef<'a>,
+    type_name: String,
+    val_name: String,
+    optional: bool,
+    description: Option<String>,
+    desc_col: Option<scraper::ElementRef<'a>>,
+}
+
+fn parse_elem<'a>(tr: scraper::ElementRef<'a>) -> Result<ParsedElem<'a>, ParseError> {
+    let tds = tr
+        .children()
+        .filter_map(ElementRef::wrap)
+        .collect::<Vec<_>>();
+    if tds.len() < 2 {
+        return Err(
+            ParseError::structure(format!("Children tds: {} (must be 3)", tds.len())).at(tr),
+        );
+    }
+
+    let desc_col = if tds.len() == 3 { Some(tds[2]) } else { None };
+
+    let prop_type = tds[0]
+        .text()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let prop_td = tds[1];
+    let optional = prop_td
+        .select(&Selector::parse("span.optional").unwrap())
+        .count()
+        == 1;
+
+    Ok(ParsedElem {
+        row: tr,
+        type_name: prop_type,
+        val_name: prop_td
+            .text()
+            .nth(if optional { 1 } else { 0 })
+            .ok_or_else(|| ParseError::new(ParseErrorKind::Missing("element name")).at(prop_td))?
+            .trim()
+            .to_owned(),
+        optional,
+        description: desc_col.and_then(markdown::from_element),
+        desc_col,
+    })
+}
+
+fn parse_name(div: scraper::ElementRef, title_selector: &str) -> Result<String, ParseError> {
+    let name_selector = Selector::parse(title_selector).unwrap();
+    match util::take_one(div.select(&name_selector)) {
+        util::TakeResult::One(e) => Ok(e.inner_html().trim().to_owned()),
+        util::TakeResult::Zero => Err(ParseError::new(ParseErrorKind::Missing("name")).at(div)),
+        util::TakeResult::More => Err(ParseError::new(ParseErrorKind::Multiple("names")).at(div)),
+    }
+}
+
+fn parse_description(div: scraper::ElementRef, title_selector: &str) -> Option<String> {
+    let description_selector =
+        Selector::parse(&format!("{} ~ div.description", title_selector)).unwrap();
+    div.select(&description_selector)
+        .next()
+        .and_then(markdown::from_element)
+}
+
+fn parse_type(type_div: scraper::ElementRef) -> Result<api::Type, ParseError> {
+    let name = parse_name(type_div, r#"h3[id^="type-"]"#)?;
+    let description = markdown::from_element(type_div);
+    let tr_selector = Selector::parse(r#"h3[id^="type-"] ~ table > tbody > tr"#).unwrap();
+    let trs = type_div.select(&tr_selector).collect::<Vec<_>>();
+    let availability = availability_of(&description);
+    if trs.is_empty() {
+        return Ok(api::Type::new_data(name, description).with_availability(availability));
+    }
+
+    let mut index = 0;
+    let mut methods = vec![];
+    let mut properties = vec![];
+    let mut optional_properties = vec![];
+    let mut events = vec![];
+
+    while index < trs.len() {
+        let tr = trs[index];
+        let prop_type = match util::take_one(
+            tr.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|e| e.value().name() == "th"),
+        ) {
+            util::TakeResult::One(e) => e.inner_html(),
+            _ => return Err(ParseError::structure("Invalid type header").at(tr)),
+        };
+        let prop_type = prop_type.trim();
+        index += 1;
+        let start_index = index;
+        while index < trs.len()
+            && trs[index]
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|e| e.value().name() == "th")
+                .count()
+                == 0
+        {
+            index += 1;
+        }
+
+        match prop_type {
+            "Enum" => {
+                let variants = parse_enum_variants(&trs[start_index..index]);
+                return Ok(api::Type::new_enum(name, description, variants)
+                    .with_availability(availability));
+            }
+            "properties" => {
+                for tr in &trs[start_index..index] {
+                    let elem = parse_elem(*tr)?;
+                    if elem.desc_col.is_none() {
+                        return Err(ParseError::structure("Children tds must be 3").at(*tr));
+                    }
+                    let optional = elem.optional;
+                    let element = parse_element(elem, &name)?;
+                    if optional {
+                        optional_properties.push(element);
+                    } else {
+                        properties.push(element);
+                    }
+                }
+            }
+            "methods" => {
+                for tr in &trs[start_index..index] {
+                    let method_div = match util::take_one(
+                        tr.children()
+                            .filter_map(ElementRef::wrap)
+                            .filter(|e| e.value().name() == "td"),
+                    ) {
+                        util::TakeResult::One(td) => match util::take_one(
+                            td.children()
+                                .filter_map(ElementRef::wrap)
+                                .filter(|e| e.value().name() == "div"),
+                        ) {
+                            util::TakeResult::One(div) => div,
+                            _ => {
+                                return Err(ParseError::new(ParseErrorKind::Missing("div")).at(*tr))
+                            }
+                        },
+                        _ => return Err(ParseError::new(ParseErrorKind::Missing("td")).at(*tr)),
+                    };
+                    methods.push(parse_method(method_div, "h4")?);
+                }
+            }
+            "events" => {
+                for tr in &trs[start_index..index] {
+                    let event_div = match util::take_one(
+                        tr.children()
+                            .filter_map(ElementRef::wrap)
+                            .filter(|e| e.value().name() == "td"),
+                    ) {
+                        util::TakeResult::One(td) => match util::take_one(
+                            td.children()
+                                .filter_map(ElementRef::wrap)
+                                .filter(|e| e.value().name() == "div"),
+                        ) {
+                            util::TakeResult::One(div) => div,
+                            _ => {
+                                return Err(ParseError::new(ParseErrorKind::Missing("div")).at(*tr))
+                            }
+                        },
+                        _ => return Err(ParseError::new(ParseErrorKind::Missing("td")).at(*tr)),
+                    };
+                    events.push(parse_inner_event(event_div)?);
+                }
+            }
+            _ => {
+                return Err(
+                    ParseError::structure(format!("Invalid type section: {}", prop_type))
+                        .at(trs[start_index - 1]),
+                )
+            }
+        }
+    }
+
+    Ok(api::Type::new_struct(
+        name,
+        description,
+        properties,
+        optional_properties,
+        methods,
+        events,
+    )
+    .with_availability(availability))
+}
+
+fn parse_enum_variants(trs: &[scraper::ElementRef]) -> Vec<api::EnumVariant> {
+    let dt_selector = Selector::parse("dt").unwrap();
+    let code_selector = Selector::parse("code").unwrap();
+    let mut variants = vec![];
+
+    for tr in trs {
+        let dts = tr.select(&dt_selector).collect::<Vec<_>>();
+        if !dts.is_empty() {
+            for dt in dts {
+                let dd = dt
+                    .next_siblings()
+                    .filter_map(ElementRef::wrap)
+                    .take_while(|e| e.value().name() != "dt")
+                    .find(|e| e.value().name() == "dd");
+                variants.push(api::EnumVariant::new(
+                    enum_value(dt),
+                    dd.and_then(markdown::from_element),
+                ));
+            }
+            continue;
+        }
+
+        let tds = tr
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|e| e.value().name() == "td")
+            .collect::<Vec<_>>();
+        if tds.len() >= 2 {
+            variants.push(api::EnumVariant::new(
+                enum_value(tds[0]),
+                markdown::from_element(tds[tds.len() - 1]),
+            ));
+        } else if let Some(td) = tds.first() {
+            let codes = td.select(&code_selector).collect::<Vec<_>>();
+            if codes.is_empty() {
+                variants.push(api::EnumVariant::new(enum_value(*td), None));
+            } else {
+                variants.extend(
+                    codes
+                        .into_iter()
+                        .map(|code| api::EnumVariant::new(enum_value(code), None)),
+                );
+            }
+        }
+    }
+
+    variants.retain(|v| !v.value().is_empty());
+    variants
+}
+
+fn enum_value(elem: scraper::ElementRef) -> String {
+    elem.text()
+        .collect::<String>()
+        .trim()
+        .trim_matches('"')
+        .to_owned()
+}
+
+fn parse_event(event_div: scraper::ElementRef) -> Result<api::Event, ParseError> {
+    let method = parse_method(event_div, "div.description > div > h4")?;
+    let name = parse_name(event_div, r#"h3[id^="event-"]"#)?;
+    let description = parse_description(event_div, r#"h3[id^="event-"]"#);
+    let availability = availability_of(&description);
+    Ok(api::Event::new(name, method, description).with_availability(availability))
+}
+
+fn parse_inner_event(event_div: scraper::ElementRef) -> Result<api::Event, ParseError> {
+    let method = parse_method(event_div, "h4")?;
+    let name_selector = Selector::parse("div.summary > code.prettyprint").unwrap();
+    let name = event_div
+        .select(&name_selector)
+        .next()
+        .ok_or_else(|| ParseError::new(ParseErrorKind::Missing("event summary")).at(event_div))?
+        .inner_html()
+        .trim()
+        .split('.')
+        .next()
+        .ok_or_else(|| ParseError::structure("Invalid event code structure").at(event_div))?
+        .to_owned();
+    let description = method.description().map(str::to_owned);
+    let availability = method.availability().clone();
+    Ok(api::Event::new(name, method, description).with_availability(availability))
+}
+
+fn parse_method(
+    method_div: scraper::ElementRef,
+    title_selector: &str,
+) -> Result<api::Method, ParseError> {
+    let name = parse_name(method_div, title_selector)?;
+    let tbody_selector = Selector::parse(&format!(
+        r#"{} ~ div.description > table > tbody"#,
+        title_selector
+    ))
+    .unwrap();
+
+    let (arguments, returns) = match util::take_one(method_div.select(&tbody_selector)) {
+        util::TakeResult::Zero => (vec![], None),
+        util::TakeResult::One(e) => (parse_method_body(e)?, parse_returns(e)?),
+        _ => {
+            return Err(
+                ParseError::new(ParseErrorKind::Multiple("parameter tables")).at(method_div),
+            )
+        }
+    };
+    let description = parse_description(method_div, title_selector);
+
+    let summary_selector = Selector::parse(&format!("{} ~ div.summary", title_selector)).unwrap();
+    let summary = method_div
+        .select(&summary_selector)
+        .next()
+        .map(|e| e.text().collect::<Vec<_>>().join(" "))
+        .unwrap_or_default();
+    let prefix = summary_prefix(&summary);
+    let returns = match returns {
+        Some(returns) => Some(returns),
+        None => prefix.as_deref().and_then(parse_summary_returns),
+    };
+    // Either the summary is prefixed with the promise, as in "Promise<Tab>
+    // chrome.tabs.get(...)", or the description has a sentence saying so.
+    let supports_promise = prefix
+        .as_deref()
+        .is_some_and(|p| p == "Promise" || p.starts_with("Promise<"))
+        || description.as_deref().is_some_and(|d| {
+            d.split(". ").any(|sentence| {
+                let sentence = sentence.trim();
+                sentence.starts_with("Promises are supported")
+                    || sentence.contains(" returns a Promise")
+            })
+        });
+
+    let availability = availability_of(&description);
+    let permissions = description
+        .as_deref()
+        .map(|d| {
+            d.split(". ")
+                .filter(|sentence| sentence.contains("permission"))
+                .flat_map(permission_names)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(
+        api::Method::new(name, arguments, returns, supports_promise, description)
+            .with_availability(availability)
+            .with_permissions(permissions),
+    )
+}
+
+fn is_returns_header(tr: &scraper::ElementRef) -> bool {
+    tr.children()
+        .filter_map(ElementRef::wrap)
+        .any(|e| e.value().name() == "th" && e.text().collect::<String>().trim() == "Returns")
+}
+
+fn parse_returns(args_tbody: scraper::ElementRef) -> Result<Option<api::Returns>, ParseError> {
+    let tr = match args_tbody
+        .children()
+        .filter_map(ElementRef::wrap)
+        .skip_while(|tr| !is_returns_header(tr))
+        .nth(1)
+    {
+        Some(tr) => tr,
+        None => return Ok(None),
+    };
+
+    let tds = tr
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "td")
+        .collect::<Vec<_>>();
+    let type_name = match tds.first() {
+        Some(td) => td.text().collect::<Vec<_>>().join(" "),
+        None => return Err(ParseError::new(ParseErrorKind::Missing("return type")).at(tr)),
+    };
+    let description = if tds.len() > 1 {
+        markdown::from_element(tds[tds.len() - 1])
+    } else {
+        None
+    };
+
+    Ok(Some(api::Returns::new(
+        api::TypeRef::parse(&type_name.split_whitespace().collect::<Vec<_>>().join(" "))
+            .map_err(|e| e.at(tr))?,
+        description,
+    )))
+}
+
+// Summaries of synchronous methods are prefixed with the return type, as in
+// "string chrome.runtime.getURL(string path)".
+fn summary_prefix(summary: &str) -> Option<String> {
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    let prefix = summary[..summary.find("chrome.")?].trim();
+    Some(prefix.to_owned()).filter(|p| !p.is_empty())
+}
+
+fn parse_summary_returns(prefix: &str) -> Option<api::Returns> {
+    if prefix == "void" || prefix.starts_with("Promise") {
+        return None;
+    }
+
+    let type_ref = api::TypeRef::parse(prefix).ok()?;
+    Some(api::Returns::new(type_ref, None))
+}
+
+fn parse_method_body(args_tbody: scraper::ElementRef) -> Result<Vec<api::Argument>, ParseError> {
+    let mut result = vec![];
+    for tr in args_tbody
+        .children()
+        .filter_map(ElementRef::wrap)
+        .take_while(|tr| !is_returns_header(tr))
+        .filter(|&e| e.value().id().is_some())
+    {
+        let elem = parse_elem(tr)?;
+
+        let arg = if elem.type_name == "function" {
+            let desc_col = elem.desc_col.ok_or_else(|| {
+                ParseError::new(ParseErrorKind::Missing("callback description")).at(tr)
+            })?;
+            let mut tbodies = vec![];
+            for table in desc_col
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|e| e.value().name() == "table")
+            {
+                match util::take_one(
+                    table
+                        .children()
+                        .filter_map(ElementRef::wrap)
+                        .filter(|e| e.value().name() == "tbody"),
+                ) {
+                    util::TakeResult::One(e) => tbodies.push(e),
+                    util::TakeResult::Zero => {}
+                    util::TakeResult::More => {
+                        return Err(ParseError::new(ParseErrorKind::Multiple("tbody")).at(table))
+                    }
+                }
+            }
+            let callback_args = match util::take_one(tbodies.into_iter()) {
+                util::TakeResult::Zero => vec![],
+                util::TakeResult::One(tbody) => parse_method_body(tbody)?,
+                _ => {
+                    return Err(ParseError::new(ParseErrorKind::Multiple(
+                        "callback parameter tables",
+                    ))
+                    .at(tr))
+                }
+            };
+            let availability = availability_of(&elem.description);
+            let method =
+                api::Method::new(elem.val_name, callback_args, None, false, elem.description)
+                    .with_availability(availability);
+            api::Argument::new_callback(method, elem.optional)
+        } else {
+            let optional = elem.optional;
+            api::Argument::new_element(parse_element(elem, "")?, optional)
+        };
+
+        result.push(arg);
+    }
+
+    Ok(result)
+}
+
+// `parent` names the object holding the field, and is empty for parameters.
+fn parse_element(elem: ParsedElem, parent: &str) -> Result<api::Element, ParseError> {
+    let mut type_ref = api::TypeRef::parse(&elem.type_name).map_err(|e| e.at(elem.row))?;
+
+    if let Some(fields_tbody) = elem.desc_col.and_then(nested_tbody) {
+        if let Some(object) = type_ref.inline_object_mut() {
+            let name = format!("{}{}", parent, util::pascal_case(&elem.val_name));
+            let (elements, optional_elements) = parse_fields(fields_tbody, &name)?;
+            *object = api::TypeRef::Object(Some(Box::new(api::Type::new_struct(
+                name,
+                None,
+                elements,
+                optional_elements,
+                vec![],
+                vec![],
+            ))));
+        }
+    }
+
+    let availability = availability_of(&elem.description);
+    let row = elem.row;
+    Ok(api::Element::new(type_ref, elem.val_name, elem.description)
+        .map_err(|e| e.at(row))?
+        .with_availability(availability))
+}
+
+fn nested_tbody(td: scraper::ElementRef) -> Option<scraper::ElementRef> {
+    td.children()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "table")
+        .flat_map(|t| {
+            t.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|e| e.value().name() == "tbody")
+        })
+        .next()
+}
+
+fn parse_fields(
+    tbody: scraper::ElementRef,
+    parent: &str,
+) -> Result<(Vec<api::Element>, Vec<api::Element>), ParseError> {
+    let mut elements = vec![];
+    let mut optional_elements = vec![];
+
+    for tr in tbody.children().filter_map(ElementRef::wrap).filter(|tr| {
+        tr.children()
+            .filter_map(ElementRef::wrap)
+            .all(|e| e.value().name() == "td")
+    }) {
+        let elem = parse_elem(tr)?;
+        if elem.optional {
+            optional_elements.push(parse_element(elem, parent)?);
+        } else {
+            elements.push(parse_element(elem, parent)?);
+        }
+    }
+
+    Ok((elements, optional_elements))
+}
+
+fn parse_properties(
+    prop_table: scraper::ElementRef,
+    reporter: &mut Reporter,
+) -> Result<Vec<api::Property>, ParseError> {
+    let tbody = match util::take_one(prop_table.children().filter_map(ElementRef::wrap)) {
+        util::TakeResult::One(tbody) => tbody,
+        util::TakeResult::More => {
+            return Err(ParseError::new(ParseErrorKind::Multiple("tbody")).at(prop_table))
+        }
+        util::TakeResult::Zero => {
+            return Err(ParseError::new(ParseErrorKind::Missing("tbody")).at(prop_table))
+        }
+    };
+
+    let mut result = vec![];
+    for tr in tbody.children().filter_map(ElementRef::wrap) {
+        match parse_property(tr, reporter) {
+            Ok(property) => result.push(property),
+            Err(e) => {
+                let e = match tr.value().id() {
+                    Some(id) => e.in_item(id.trim_start_matches("property-")),
+                    None => e,
+                };
+                reporter.report(Severity::Error, e)?;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_property(
+    tr: scraper::ElementRef,
+    reporter: &mut Reporter,
+) -> Result<api::Property, ParseError> {
+    let elem = parse_elem(tr)?;
+    if elem.optional {
+        return Err(ParseError::structure("Properties cannot be optional").at(tr));
+    }
+    let availability = availability_of(&elem.description);
+    if elem.type_name != "object" {
+        let (type_ref, value) = if elem
+            .type_name
+            .chars()
+            .next()
+            .map(|c| !c.is_ascii_alphabetic())
+            .unwrap_or(false)
+        {
+            let value = parse_literal(&elem.type_name).ok_or_else(|| {
+                ParseError::new(ParseErrorKind::InvalidConstant(elem.type_name.clone())).at(tr)
+            })?;
+            (value.type_ref(), Some(value))
+        } else {
+            (
+                api::TypeRef::parse(&elem.type_name).map_err(|e| e.at(tr))?,
+                None,
+            )
+        };
+
+        return Ok(
+            api::Property::new_immediate(elem.val_name, type_ref, value, elem.description)
+                .with_availability(availability),
+        );
+    }
+
+    let td = match elem.desc_col {
+        Some(td) => td,
+        None => {
+            return Ok(api::Property::new_immediate(
+                elem.val_name,
+                api::TypeRef::Object(None),
+                None,
+                elem.description,
+            )
+            .with_availability(availability))
+        }
+    };
+    let tbody = match util::take_one(
+        td.children()
+            .filter_map(ElementRef::wrap)
+            .filter(|e| e.value().name() == "table")
+            .flat_map(|t| {
+                t.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|e| e.value().name() == "tbody")
+            }),
+    ) {
+        util::TakeResult::One(td) => td,
+        util::TakeResult::Zero => {
+            return Ok(api::Property::new_immediate(
+                elem.val_name,
+                api::TypeRef::Object(None),
+                None,
+                elem.description,
+            )
+            .with_availability(availability))
+        }
+        util::TakeResult::More => {
+            return Err(ParseError::new(ParseErrorKind::Multiple("method tables")).at(td))
+        }
+    };
+
+    let mut methods = vec![];
+    for tr in tbody.children().filter_map(ElementRef::wrap) {
+        match parse_method(tr, r#"h3[id^="method-"]"#) {
+            Ok(method) => methods.push(method),
+            Err(e) => {
+                // Keep the property, just without its methods.
+                reporter.report(Severity::Warning, e.in_item(&elem.val_name))?;
+                return Ok(api::Property::new_immediate(
+                    elem.val_name,
+                    api::TypeRef::Object(None),
+                    None,
+                    elem.description,
+                )
+                .with_availability(availability));
+            }
+        }
+    }
+
+    Ok(
+        api::Property::new_object(elem.val_name, methods, elem.description)
+            .with_availability(availability),
+    )
+}
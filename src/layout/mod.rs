@@ -0,0 +1,59 @@
+pub(crate) mod current;
+pub(crate) mod legacy;
+
+use crate::error::ParseError;
+use crate::{api, naming, Reporter};
+use scraper::Html;
+
+// The markup generations of the extension API reference on developer.chrome.com.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Layout {
+    // `/extensions/<name>` pages with a `div.api-reference` block.
+    Legacy,
+    // `/docs/extensions/reference/api/<name>` pages.
+    Current,
+}
+
+impl Layout {
+    pub fn detect(html: &str) -> Option<Layout> {
+        Layout::detect_document(&Html::parse_document(html))
+    }
+
+    pub(crate) fn detect_document(api_root: &Html) -> Option<Layout> {
+        if legacy::is_layout(api_root) {
+            Some(Layout::Legacy)
+        } else if current::is_layout(api_root) {
+            Some(Layout::Current)
+        } else {
+            None
+        }
+    }
+
+    pub fn index_url(self) -> &'static str {
+        match self {
+            Layout::Legacy => legacy::INDEX_URL,
+            Layout::Current => current::INDEX_URL,
+        }
+    }
+
+    pub(crate) fn parse_index(self, api_root: &Html) -> Vec<(String, String)> {
+        match self {
+            Layout::Legacy => legacy::parse_index(api_root),
+            Layout::Current => current::parse_index(api_root),
+        }
+    }
+
+    pub(crate) fn parse_namespace(
+        self,
+        name: &str,
+        api_root: &Html,
+        reporter: &mut Reporter,
+    ) -> Result<api::Namespace, ParseError> {
+        let mut namespace = match self {
+            Layout::Legacy => legacy::parse_namespace(name, api_root, reporter),
+            Layout::Current => current::parse_namespace(name, api_root, reporter),
+        }?;
+        naming::disambiguate(&mut namespace);
+        Ok(namespace)
+    }
+}
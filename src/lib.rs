@@ -2,403 +2,280 @@ extern crate reqwest;
 extern crate scraper;
 
 pub mod api;
+pub mod builder;
+pub mod compat;
+pub mod error;
+pub mod layout;
+pub mod link;
+pub(crate) mod markdown;
+pub(crate) mod naming;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+pub mod tree;
 pub(crate) mod util;
+pub mod visit;
 
-use scraper::{ElementRef, Html, Selector};
-use std::convert::TryFrom;
+use error::{Diagnostic, ParseError, ParseErrorKind, Severity};
+pub use layout::Layout;
+use scraper::Html;
 
-pub async fn api_pages() -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
-    const BASE: &str = "https://developer.chrome.com/extensions/";
+pub async fn api_pages(
+    layout: Layout,
+) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let html = util::get_cached(layout.index_url()).await?;
+    Ok(parse_index_html(layout, &html))
+}
 
-    let api_root = Html::parse_document(&util::get_cached(&format!("{}api_index", BASE)).await?);
-    let stable_api_selector =
-        Selector::parse("#stable_apis ~ table:nth-of-type(1) tr td:nth-of-type(1) a").unwrap();
+// Lists the `(namespace, url)` pairs linked from an index page.
+pub fn parse_index_html(layout: Layout, html: &str) -> Vec<(String, String)> {
+    layout.parse_index(&Html::parse_document(html))
+}
 
-    Ok(api_root
-        .select(&stable_api_selector)
-        .map(|link| link.value().attr("href").unwrap())
-        .map(|space| (space.to_owned(), format!("{}{}", BASE, space)))
-        .collect())
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    strict: bool,
+    layout: Option<Layout>,
 }
 
-pub async fn parse_apis(
-    name: &str,
-    url: &str,
-) -> Result<api::Namespace, Box<dyn std::error::Error>> {
-    let api_root = Html::parse_document(&util::get_cached(url).await?);
-    let api_selector = Selector::parse("div.api-reference > *").unwrap();
-    let api_info = api_root.select(&api_selector).collect::<Vec<_>>();
-    let mut index = 0;
-    let mut types = vec![];
-    let mut methods = vec![];
-    let mut events = vec![];
-    let mut properties = vec![];
+impl ParseOptions {
+    // In strict mode the first diagnostic, even a warning, fails the whole page.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
 
-    while index < api_info.len() {
-        let title = api_info[index].value();
-        let api_type = api::ApiType::try_from(title.id().ok_or("Invalid API structure")?)?;
-        index += 1;
-        let _initial = index;
-        while index < api_info.len() && api_info[index].value().name() != "h2" {
-            match api_type {
-                api::ApiType::Types => {
-                    if let Ok(t) = parse_type(api_info[index]) {
-                        types.push(t);
-                    }
-                }
-                api::ApiType::Methods => {
-                    if let Ok(m) = parse_method(api_info[index], "h3") {
-                        methods.push(m);
-                    }
-                }
-                api::ApiType::Events => {
-                    if let Ok(e) = parse_event(api_info[index]) {
-                        events.push(e);
-                    }
-                }
-                api::ApiType::Properties => {
-                    if let Ok(ps) = parse_properties(api_info[index]) {
-                        properties = ps;
-                    }
-                }
-            }
-            index += 1;
-        }
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    // Without an explicit layout, it is detected from the page.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = Some(layout);
+        self
     }
 
-    Ok(api::Namespace::new(
-        name.to_owned(),
-        types,
-        properties,
-        methods,
-        events,
-    ))
+    pub fn layout(&self) -> Option<Layout> {
+        self.layout
+    }
 }
 
-struct ParsedElem<'a> {
-    type_name: String,
-    val_name: String,
-    optional: bool,
-    desc_col: Option<scraper::ElementRef<'a>>,
+struct Reporter<'a> {
+    options: &'a ParseOptions,
+    namespace: &'a str,
+    section: String,
+    diagnostics: Vec<Diagnostic>,
 }
 
-fn parse_elem<'a>(tr: scraper::ElementRef<'a>) -> Result<ParsedElem<'a>, String> {
-    let tds = tr
-        .children()
-        .filter_map(ElementRef::wrap)
-        .collect::<Vec<_>>();
-    if tds.len() < 2 {
-        return Err(format!("Children tds: {} (must be 3)", tds.len()));
+impl Reporter<'_> {
+    fn report(&mut self, severity: Severity, error: ParseError) -> Result<(), ParseError> {
+        let error = error.in_section(&self.section).in_namespace(self.namespace);
+        if self.options.strict {
+            return Err(error);
+        }
+        self.diagnostics.push(Diagnostic::new(severity, error));
+        Ok(())
     }
+}
 
-    let desc_col = if tds.len() == 3 { Some(tds[2]) } else { None };
+pub async fn parse_apis(
+    name: &str,
+    url: &str,
+    options: &ParseOptions,
+) -> Result<(api::Namespace, Vec<Diagnostic>), Box<dyn std::error::Error>> {
+    let html = util::get_cached(url).await?;
+    Ok(parse_api_html_with(name, &html, options)?)
+}
 
-    let prop_type = tds[0]
-        .text()
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join(" ");
-    let prop_td = tds[1];
-    let optional = prop_td
-        .select(&Selector::parse("span.optional").unwrap())
-        .count()
-        == 1;
+pub fn parse_api_html(name: &str, html: &str) -> Result<api::Namespace, ParseError> {
+    parse_api_html_with(name, html, &ParseOptions::default()).map(|(namespace, _)| namespace)
+}
 
-    Ok(ParsedElem {
-        type_name: prop_type,
-        val_name: prop_td
-            .text()
-            .nth(if optional { 1 } else { 0 })
-            .ok_or("Invalid Element structure")?
-            .trim()
-            .to_owned(),
-        optional,
-        desc_col: desc_col,
-    })
+pub fn parse_api_html_with(
+    name: &str,
+    html: &str,
+    options: &ParseOptions,
+) -> Result<(api::Namespace, Vec<Diagnostic>), ParseError> {
+    let api_root = Html::parse_document(html);
+    let layout = match options
+        .layout
+        .or_else(|| Layout::detect_document(&api_root))
+    {
+        Some(layout) => layout,
+        None => {
+            return Err(ParseError::new(ParseErrorKind::Missing("API reference")).in_namespace(name))
+        }
+    };
+    let mut reporter = Reporter {
+        options,
+        namespace: name,
+        section: String::new(),
+        diagnostics: vec![],
+    };
+    let namespace = layout.parse_namespace(name, &api_root, &mut reporter)?;
+    Ok((namespace, reporter.diagnostics))
 }
 
-fn parse_name(div: scraper::ElementRef, title_selector: &str) -> Result<String, String> {
-    let name_selector = Selector::parse(&format!(r#"{}"#, title_selector)).unwrap();
-    match util::take_one(div.select(&name_selector)) {
-        util::TakeResult::One(e) => Ok(e.inner_html().trim().to_owned()),
-        util::TakeResult::Zero => Err("No name found".to_owned()),
-        util::TakeResult::More => Err("Multiple names found".to_owned()),
-    }
+fn code_spans(markdown: &str) -> impl Iterator<Item = &str> {
+    markdown.split('`').skip(1).step_by(2)
 }
 
-fn parse_type(type_div: scraper::ElementRef) -> Result<api::Type, String> {
-    let name = parse_name(type_div, r#"h3[id^="type-"]"#)?;
-    let tr_selector = Selector::parse(r#"h3[id^="type-"] ~ table > tbody > tr"#).unwrap();
-    let trs = type_div.select(&tr_selector).collect::<Vec<_>>();
-    if trs.len() == 0 {
-        return Ok(api::Type::new_data(name));
+// Permissions show up as quoted strings or host patterns such as `<all_urls>` in code spans.
+fn permission_names(markdown: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for code in code_spans(markdown) {
+        let code = code.trim();
+        let name = if code.len() > 2 && code.starts_with('"') && code.ends_with('"') {
+            &code[1..code.len() - 1]
+        } else if code.starts_with('<') && code.ends_with('>') {
+            code
+        } else {
+            continue;
+        };
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_owned());
+        }
     }
+    names
+}
 
-    let mut index = 0;
-    let mut methods = vec![];
-    let mut properties = vec![];
-    let mut optional_properties = vec![];
-    let mut events = vec![];
+fn parse_availability(text: &str) -> api::Availability {
+    let words = text
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+        .collect::<Vec<_>>();
 
-    while index < trs.len() {
-        let tr = trs[index];
-        let prop_type = match util::take_one(
-            tr.children()
-                .filter_map(ElementRef::wrap)
-                .filter(|e| e.value().name() == "th"),
-        ) {
-            util::TakeResult::One(e) => e.inner_html(),
-            _ => return Err("Invalid type header".to_owned()),
-        };
-        let prop_type = prop_type.trim();
-        index += 1;
-        let start_index = index;
-        while index < trs.len()
-            && trs[index]
-                .children()
-                .filter_map(ElementRef::wrap)
-                .filter(|e| e.value().name() == "th")
-                .count()
-                == 0
-        {
-            index += 1;
+    let mut since = None;
+    for (i, window) in words.windows(3).enumerate() {
+        if window[0].eq_ignore_ascii_case("since") && window[1] == "Chrome" {
+            let deprecated = i > 0 && words[i - 1].eq_ignore_ascii_case("deprecated");
+            if let (Ok(version), false) = (window[2].parse::<u32>(), deprecated) {
+                since = Some(version);
+                break;
+            }
         }
+    }
 
-        match prop_type {
-            "Enum" => return Ok(api::Type::new_enum(name)),
-            "properties" => {
-                for tr in &trs[start_index..index] {
-                    let elem = parse_elem(*tr)?;
-                    if elem.desc_col.is_none() {
-                        return Err("Children tds must be 3".to_owned());
-                    }
-                    if elem.optional {
-                        optional_properties.push(api::Element::new(elem.type_name, elem.val_name));
-                    } else {
-                        properties.push(api::Element::new(elem.type_name, elem.val_name));
+    let deprecated = text
+        .split("\n\n")
+        .map(|p| p.replace('*', ""))
+        .find(|p| p.trim_start().starts_with("Deprecated"))
+        .map(|p| p.trim().to_owned());
+
+    let mut channels = vec![];
+    let mut platforms: Vec<String> = vec![];
+    for clause in text.split(['.', ',', ';', '\n']) {
+        let words = clause
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        for (i, word) in words.iter().enumerate() {
+            if word == "channel" || word == "channels" {
+                channels.extend(listed_before(&words, i, channel_at));
+            } else if word == "only" {
+                // "ChromeOS only" or "Windows and Mac only" closing a clause, or
+                // "only on ChromeOS".
+                let listed = match words.get(i + 1).map(String::as_str) {
+                    None => listed_before(&words, i, platform_at),
+                    Some("on") | Some("for") => listed_after(&words, i + 2, platform_at),
+                    Some(_) => vec![],
+                };
+                for platform in listed {
+                    if !platforms.contains(&platform) {
+                        platforms.push(platform);
                     }
                 }
             }
-            "methods" => {
-                for tr in &trs[start_index..index] {
-                    let method_div = match util::take_one(
-                        tr.children()
-                            .filter_map(ElementRef::wrap)
-                            .filter(|e| e.value().name() == "td"),
-                    ) {
-                        util::TakeResult::One(td) => match util::take_one(
-                            td.children()
-                                .filter_map(ElementRef::wrap)
-                                .filter(|e| e.value().name() == "div"),
-                        ) {
-                            util::TakeResult::One(div) => div,
-                            _ => return Err("div not found in Type".to_owned()),
-                        },
-                        _ => return Err("td not found in Type".to_owned()),
-                    };
-                    methods.push(parse_method(method_div, "h4")?);
-                }
-            }
-            "events" => {
-                for tr in &trs[start_index..index] {
-                    let event_div = match util::take_one(
-                        tr.children()
-                            .filter_map(ElementRef::wrap)
-                            .filter(|e| e.value().name() == "td"),
-                    ) {
-                        util::TakeResult::One(td) => match util::take_one(
-                            td.children()
-                                .filter_map(ElementRef::wrap)
-                                .filter(|e| e.value().name() == "div"),
-                        ) {
-                            util::TakeResult::One(div) => div,
-                            _ => return Err("div not found in Event".to_owned()),
-                        },
-                        _ => return Err("td not found in Event".to_owned()),
-                    };
-                    events.push(parse_inner_event(event_div)?);
-                }
-            }
-            _ => return Err("Invalid type".to_owned()),
         }
     }
+    // A feature listed for several channels is available from the most stable one.
+    let channel = channels
+        .into_iter()
+        .min_by_key(|c| *c as u8)
+        .unwrap_or(api::Channel::Stable);
 
-    Ok(api::Type::new_struct(
-        name,
-        properties,
-        optional_properties,
-        methods,
-        events,
-    ))
+    api::Availability::new(since, channel, deprecated, platforms)
 }
 
-fn parse_event(event_div: scraper::ElementRef) -> Result<api::Event, String> {
-    let method = parse_method(event_div, "div.description > div > h4")?;
-    let name = parse_name(event_div, r#"h3[id^="event-"]"#)?;
-    Ok(api::Event::new(name, method))
-}
+// Matches a name spanning one or more words at `words[i..]`, returning it and its length.
+type Matcher<T> = fn(&[String], usize) -> Option<(T, usize)>;
 
-fn parse_inner_event(event_div: scraper::ElementRef) -> Result<api::Event, String> {
-    let method = parse_method(event_div, "h4")?;
-    let name_selector = Selector::parse("div.summary > code.prettyprint").unwrap();
-    let name = event_div
-        .select(&name_selector)
-        .next()
-        .ok_or("Invalid event name structure".to_owned())?
-        .inner_html()
-        .trim()
-        .split('.')
-        .next()
-        .ok_or("Invalid event code structure".to_owned())?
-        .to_owned();
-    Ok(api::Event::new(name, method))
+fn channel_at(words: &[String], i: usize) -> Option<(api::Channel, usize)> {
+    let channel = match words.get(i)?.as_str() {
+        "beta" => api::Channel::Beta,
+        "dev" => api::Channel::Dev,
+        "canary" | "trunk" => api::Channel::Canary,
+        _ => return None,
+    };
+    Some((channel, 1))
 }
 
-fn parse_method(
-    method_div: scraper::ElementRef,
-    title_selector: &str,
-) -> Result<api::Method, String> {
-    let name = parse_name(method_div, title_selector)?;
-    let tbody_selector = Selector::parse(&format!(
-        r#"{} ~ div.description > table > tbody"#,
-        title_selector
-    ))
-    .unwrap();
-
-    let arguments = match util::take_one(method_div.select(&tbody_selector)) {
-        util::TakeResult::Zero => vec![],
-        util::TakeResult::One(e) => parse_method_body(e)?,
-        _ => return Err("Unsupported method len".to_owned()),
+fn platform_at(words: &[String], i: usize) -> Option<(String, usize)> {
+    let (id, len) = match (words.get(i)?.as_str(), words.get(i + 1).map(String::as_str)) {
+        ("chrome", Some("os")) => ("chromeos", 2),
+        ("chromeos", _) => ("chromeos", 1),
+        ("windows", _) => ("win", 1),
+        ("mac", _) | ("macos", _) => ("mac", 1),
+        ("linux", _) => ("linux", 1),
+        ("android", _) => ("android", 1),
+        _ => return None,
     };
-
-    Ok(api::Method::new(name, arguments))
+    Some((id.to_owned(), len))
 }
 
-fn parse_method_body(args_tbody: scraper::ElementRef) -> Result<Vec<api::Argument>, String> {
-    let mut result = vec![];
-    for tr in args_tbody
-        .children()
-        .filter_map(ElementRef::wrap)
-        .filter(|&e| e.value().id().is_some())
-    {
-        let elem = parse_elem(tr)?;
-
-        let arg = if elem.type_name == "function" {
-            let tbody = elem
-                .desc_col
-                .ok_or("No info for callback found".to_owned())?
-                .children()
-                .filter_map(ElementRef::wrap)
-                .filter(|e| e.value().name() == "table")
-                .map(|t| {
-                    match util::take_one(
-                        t.children()
-                            .filter_map(ElementRef::wrap)
-                            .filter(|e| e.value().name() == "tbody"),
-                    ) {
-                        util::TakeResult::One(e) => e,
-                        _ => panic!("tbody not found in method"),
-                    }
-                });
-            let callback_args = match util::take_one(tbody) {
-                util::TakeResult::Zero => vec![],
-                util::TakeResult::One(tbody) => parse_method_body(tbody)?,
-                _ => return Err("Multiple argument info found".to_owned()),
-            };
-            let method = api::Method::new(elem.val_name, callback_args);
-            api::Argument::new_callback(method, elem.optional)
-        } else {
-            let element = api::Element::new(elem.type_name, elem.val_name);
-            api::Argument::new_element(element, elem.optional)
-        };
+fn is_conjunction(word: &str) -> bool {
+    word == "and" || word == "or"
+}
 
-        result.push(arg);
+// The names listed right before `words[end]`, e.g. "Beta and Dev" in "Beta and Dev channels".
+fn listed_before<T>(words: &[String], end: usize, matcher: Matcher<T>) -> Vec<T> {
+    let mut found = vec![];
+    let mut end = end;
+    loop {
+        let matched = (1..=2.min(end))
+            .find_map(|len| matcher(words, end - len).filter(|(_, matched)| *matched == len));
+        match matched {
+            Some((item, len)) => {
+                found.insert(0, item);
+                end -= len;
+                if end > 0 && is_conjunction(&words[end - 1]) {
+                    end -= 1;
+                }
+            }
+            None => break,
+        }
     }
-
-    Ok(result)
+    found
 }
 
-fn parse_properties(prop_table: scraper::ElementRef) -> Result<Vec<api::Property>, String> {
-    let tbody = match util::take_one(prop_table.children().filter_map(ElementRef::wrap)) {
-        util::TakeResult::One(tbody) => tbody,
-        util::TakeResult::More => return Err("Multiple tbody found in Properties".to_owned()),
-        util::TakeResult::Zero => return Err("No tbody found in Properties".to_owned()),
-    };
-
-    let mut result = vec![];
-
-    for tr in tbody.children().filter_map(ElementRef::wrap) {
-        let elem = parse_elem(tr)?;
-        if elem.optional {
-            return Err("Properties cannot be optional".to_owned());
+// The names listed from `words[start]` on, e.g. "ChromeOS or Linux" in "only on ChromeOS or Linux".
+fn listed_after<T>(words: &[String], start: usize, matcher: Matcher<T>) -> Vec<T> {
+    let mut found = vec![];
+    let mut start = start;
+    while let Some((item, len)) = matcher(words, start) {
+        found.push(item);
+        start += len;
+        if words.get(start).is_some_and(|w| is_conjunction(w)) {
+            start += 1;
         }
-        if elem.type_name != "object" {
-            let type_name = if elem
-                .type_name
-                .chars()
-                .next()
-                .map(|c| !c.is_ascii_alphabetic())
-                .unwrap_or(false)
-            {
-                if elem.type_name.contains('.') {
-                    "number"
-                } else {
-                    "integer"
-                }
-            } else {
-                &elem.type_name
-            };
-
-            result.push(api::Property::new_immediate(
-                elem.val_name,
-                type_name.to_owned(),
-            ));
-        } else {
-            if let Some(td) = elem.desc_col {
-                let tbody = match util::take_one(
-                    td.children()
-                        .filter_map(ElementRef::wrap)
-                        .filter(|e| e.value().name() == "table")
-                        .flat_map(|t| {
-                            t.children()
-                                .filter_map(ElementRef::wrap)
-                                .filter(|e| e.value().name() == "tbody")
-                        }),
-                ) {
-                    util::TakeResult::One(td) => td,
-                    util::TakeResult::Zero => {
-                        panic!("no table found");
-                        // result.push(api::Property::new_immediate(elem.val_name, elem.type_name));
-                        // continue;
-                    }
-                    util::TakeResult::More => {
-                        panic!("too many table found");
-                    }
-                };
-
-                let mut methods = vec![];
-                let mut flag = false;
-                for tr in tbody.children().filter_map(ElementRef::wrap) {
-                    let method = parse_method(tr, r#"h3[id^="method-"]"#);
-                    if method.is_err() {
-                        flag = true;
-                        break;
-                    }
-                    methods.push(method.unwrap());
-                }
+    }
+    found
+}
 
-                if flag {
-                    result.push(api::Property::new_immediate(elem.val_name, elem.type_name));
-                    continue;
-                }
+fn availability_of(description: &Option<String>) -> api::Availability {
+    parse_availability(description.as_deref().unwrap_or_default())
+}
 
-                result.push(api::Property::new_object(elem.val_name, methods));
-            } else {
-                result.push(api::Property::new_immediate(elem.val_name, elem.type_name));
-            }
-        }
+fn parse_literal(text: &str) -> Option<api::Literal> {
+    let text = text.trim();
+    if let Ok(i) = text.parse::<i64>() {
+        Some(api::Literal::Integer(i))
+    } else if let Ok(x) = text.parse::<f64>() {
+        api::Float::new(x).map(api::Literal::Float)
+    } else if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        Some(api::Literal::String(text[1..text.len() - 1].to_owned()))
+    } else {
+        None
     }
-
-    Ok(result)
 }
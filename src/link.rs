@@ -0,0 +1,194 @@
+use crate::api::{Element, Event, Method, Namespace, Property, Type, TypeRef};
+use crate::visit::{self, VisitorMut};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DiagnosticKind {
+    Dangling,
+    Ambiguous { candidates: Vec<String> },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    location: String,
+    reference: String,
+    kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    pub fn kind(&self) -> &DiagnosticKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            DiagnosticKind::Dangling => write!(
+                f,
+                "{}: reference to undefined type {}",
+                self.location, self.reference
+            ),
+            DiagnosticKind::Ambiguous { candidates } => write!(
+                f,
+                "{}: ambiguous reference {} (candidates: {})",
+                self.location,
+                self.reference,
+                candidates.join(", ")
+            ),
+        }
+    }
+}
+
+pub fn type_id(namespace: &str, type_name: &str) -> String {
+    format!("{}.{}", namespace, type_name)
+}
+
+// Rewrites every named type reference into its fully-qualified type ID, e.g. "Tab"
+// inside `windows` becomes "tabs.Tab". References that cannot be resolved are left as
+// they are and reported.
+pub fn link(namespaces: &mut [Namespace]) -> Vec<Diagnostic> {
+    link_with(namespaces, &[])
+}
+
+// Like `link`, but references may also resolve into `shared` namespaces that are not
+// rewritten themselves, such as the WebExtension APIs Thunderbird's `messenger.*`
+// builds on. A namespace in `namespaces` hides the `shared` one of the same name.
+pub fn link_with(namespaces: &mut [Namespace], shared: &[Namespace]) -> Vec<Diagnostic> {
+    let mut ids = HashSet::new();
+    let mut by_name: HashMap<String, Vec<String>> = HashMap::new();
+    let shared = shared
+        .iter()
+        .filter(|s| namespaces.iter().all(|n| n.name() != s.name()));
+    for namespace in namespaces.iter().chain(shared) {
+        for t in namespace.types() {
+            let id = type_id(namespace.name(), t.name());
+            by_name
+                .entry(t.name().to_owned())
+                .or_default()
+                .push(id.clone());
+            ids.insert(id);
+        }
+    }
+
+    let mut linker = Linker {
+        ids: &ids,
+        by_name: &by_name,
+        namespace: String::new(),
+        path: vec![],
+        diagnostics: vec![],
+    };
+    for namespace in namespaces.iter_mut() {
+        linker.visit_namespace_mut(namespace);
+    }
+    linker.diagnostics
+}
+
+struct Linker<'a> {
+    ids: &'a HashSet<String>,
+    by_name: &'a HashMap<String, Vec<String>>,
+    namespace: String,
+    path: Vec<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linker<'_> {
+    fn nested(&mut self, name: String, walk: impl FnOnce(&mut Self)) {
+        self.path.push(name);
+        walk(self);
+        self.path.pop();
+    }
+}
+
+impl VisitorMut for Linker<'_> {
+    fn visit_namespace_mut(&mut self, namespace: &mut Namespace) {
+        self.namespace = namespace.name().to_owned();
+        self.nested(self.namespace.clone(), |v| {
+            visit::walk_namespace_mut(v, namespace)
+        });
+    }
+
+    fn visit_type_mut(&mut self, t: &mut Type) {
+        self.nested(t.name().to_owned(), |v| visit::walk_type_mut(v, t));
+    }
+
+    fn visit_element_mut(&mut self, element: &mut Element) {
+        self.nested(element.name().to_owned(), |v| {
+            visit::walk_element_mut(v, element)
+        });
+    }
+
+    fn visit_property_mut(&mut self, property: &mut Property) {
+        self.nested(property.name().to_owned(), |v| {
+            visit::walk_property_mut(v, property)
+        });
+    }
+
+    fn visit_method_mut(&mut self, method: &mut Method) {
+        self.nested(method.name().to_owned(), |v| {
+            visit::walk_method_mut(v, method)
+        });
+    }
+
+    fn visit_event_mut(&mut self, event: &mut Event) {
+        self.nested(event.name().to_owned(), |v| visit::walk_event_mut(v, event));
+    }
+
+    fn visit_type_ref_mut(&mut self, type_ref: &mut TypeRef) {
+        if let TypeRef::Named(reference) = type_ref {
+            match resolve(&self.namespace, reference, self.ids, self.by_name) {
+                Ok(id) => *reference = id,
+                Err(kind) => self.diagnostics.push(Diagnostic {
+                    location: self.path.join("."),
+                    reference: reference.clone(),
+                    kind,
+                }),
+            }
+        }
+        visit::walk_type_ref_mut(self, type_ref);
+    }
+}
+
+fn resolve(
+    namespace: &str,
+    reference: &str,
+    ids: &HashSet<String>,
+    by_name: &HashMap<String, Vec<String>>,
+) -> Result<String, DiagnosticKind> {
+    if reference.contains('.') {
+        if ids.contains(reference) {
+            return Ok(reference.to_owned());
+        }
+        // Sibling namespaces refer to each other relative to their parent, as in
+        // "inspectedWindow.Resource" from `devtools.panels`.
+        if let Some((parent, _)) = namespace.rsplit_once('.') {
+            let id = type_id(parent, reference);
+            if ids.contains(&id) {
+                return Ok(id);
+            }
+        }
+        return Err(DiagnosticKind::Dangling);
+    }
+
+    let local = type_id(namespace, reference);
+    if ids.contains(&local) {
+        return Ok(local);
+    }
+
+    match by_name.get(reference).map(Vec::as_slice) {
+        Some([id]) => Ok(id.clone()),
+        Some(candidates) if !candidates.is_empty() => Err(DiagnosticKind::Ambiguous {
+            candidates: candidates.to_vec(),
+        }),
+        _ => Err(DiagnosticKind::Dangling),
+    }
+}
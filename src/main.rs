@@ -1,12 +1,18 @@
-use webext_parser;
-
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    for (space, url) in webext_parser::api_pages().await?.iter() {
+    let options = webext_parser::ParseOptions::default();
+    for (space, url) in webext_parser::api_pages(webext_parser::Layout::Current)
+        .await?
+        .iter()
+    {
         /* if space != "windows" {
             continue;
         } */
-        println!("{:?}", webext_parser::parse_apis(&space, &url).await?);
+        let (namespace, diagnostics) = webext_parser::parse_apis(space, url, &options).await?;
+        for diagnostic in diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        println!("{:?}", namespace);
     }
     Ok(())
 }
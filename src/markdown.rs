@@ -0,0 +1,146 @@
+use scraper::{ElementRef, Html, Node};
+
+pub(crate) fn from_element(elem: ElementRef) -> Option<String> {
+    let mut writer = Writer::default();
+    writer.children(elem);
+    writer.finish()
+}
+
+// Schema descriptions are HTML fragments rather than whole pages.
+pub(crate) fn from_html(html: &str) -> Option<String> {
+    from_element(Html::parse_fragment(html).root_element())
+}
+
+#[derive(Default)]
+struct Writer {
+    blocks: Vec<String>,
+    line: String,
+}
+
+impl Writer {
+    fn children(&mut self, elem: ElementRef) {
+        for child in elem.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(e) = ElementRef::wrap(child) {
+                        self.element(e);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, elem: ElementRef) {
+        let value = elem.value();
+        match value.name() {
+            "table" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "script" | "style" => {}
+            "div" if value.classes().any(|c| c == "summary") => {}
+            "span" if value.classes().any(|c| c == "optional") => {}
+            "code" | "tt" => {
+                let code = inline_text(elem);
+                if !code.is_empty() {
+                    self.inline(&format!("`{}`", code));
+                }
+            }
+            "a" => {
+                let mut inner = Writer::default();
+                inner.children(elem);
+                let label = inner.finish().unwrap_or_default();
+                match value.attr("href") {
+                    Some(href) if !label.is_empty() => {
+                        self.inline(&format!("[{}]({})", label, href))
+                    }
+                    _ => self.inline(&label),
+                }
+            }
+            "strong" | "b" => self.wrapped(elem, "**"),
+            "em" | "i" => self.wrapped(elem, "*"),
+            "br" => self.flush(),
+            "pre" => {
+                self.flush();
+                let code = elem.text().collect::<String>();
+                self.blocks
+                    .push(format!("```\n{}\n```", code.trim_matches('\n')));
+            }
+            "li" => {
+                self.flush();
+                self.line.push_str("- ");
+                self.children(elem);
+                self.flush();
+            }
+            "p" | "div" | "ul" | "ol" | "dl" | "dt" | "dd" | "section" | "blockquote" => {
+                self.flush();
+                self.children(elem);
+                self.flush();
+            }
+            _ => self.children(elem),
+        }
+    }
+
+    fn wrapped(&mut self, elem: ElementRef, mark: &str) {
+        let mut inner = Writer::default();
+        inner.children(elem);
+        if let Some(text) = inner.finish() {
+            self.inline(&format!("{}{}{}", mark, text, mark));
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let mut words = text.split_whitespace().peekable();
+        if words.peek().is_none() {
+            if !text.is_empty() && !self.line.is_empty() {
+                self.space();
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace) {
+            self.space();
+        }
+        while let Some(word) = words.next() {
+            self.line.push_str(word);
+            if words.peek().is_some() {
+                self.line.push(' ');
+            }
+        }
+        if text.ends_with(char::is_whitespace) {
+            self.space();
+        }
+    }
+
+    fn inline(&mut self, s: &str) {
+        self.line.push_str(s);
+    }
+
+    fn space(&mut self) {
+        if !self.line.is_empty() && !self.line.ends_with(' ') && !self.line.ends_with("- ") {
+            self.line.push(' ');
+        }
+    }
+
+    fn flush(&mut self) {
+        let line = self.line.trim();
+        if !line.is_empty() {
+            self.blocks.push(line.to_owned());
+        }
+        self.line.clear();
+    }
+
+    fn finish(mut self) -> Option<String> {
+        self.flush();
+        if self.blocks.is_empty() {
+            None
+        } else {
+            Some(self.blocks.join("\n\n"))
+        }
+    }
+}
+
+fn inline_text(elem: ElementRef) -> String {
+    elem.text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
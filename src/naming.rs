@@ -0,0 +1,112 @@
+use crate::api::*;
+use crate::util;
+use crate::visit::{self, Visitor, VisitorMut};
+use std::collections::HashMap;
+
+// Inline object types of parameters are named after the parameter alone, so that
+// `tabs.query(queryInfo)` gives `QueryInfo`. Where such a name is used more than once
+// in the namespace, by another inline type or a declared one, the clashing parameters'
+// types get their function's name in front, e.g. `CreateOptions` and `UpdateOptions`.
+pub(crate) fn disambiguate(namespace: &mut Namespace) {
+    let mut counter = Counter::default();
+    counter.visit_namespace(namespace);
+    let clashing = counter
+        .counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(name, _)| name.to_owned())
+        .collect::<Vec<_>>();
+    if clashing.is_empty() {
+        return;
+    }
+
+    Prefixer {
+        clashing,
+        scope: vec![],
+    }
+    .visit_namespace_mut(namespace);
+}
+
+#[derive(Default)]
+struct Counter<'ast> {
+    counts: HashMap<&'ast str, usize>,
+}
+
+impl<'ast> Visitor<'ast> for Counter<'ast> {
+    fn visit_type(&mut self, t: &'ast Type) {
+        *self.counts.entry(t.name()).or_default() += 1;
+        visit::walk_type(self, t);
+    }
+}
+
+// Tracks the names of the items around a parameter, which make up its prefix.
+struct Prefixer {
+    clashing: Vec<String>,
+    scope: Vec<String>,
+}
+
+impl Prefixer {
+    fn within(&mut self, name: String, walk: impl FnOnce(&mut Self)) {
+        self.scope.push(name);
+        walk(self);
+        self.scope.pop();
+    }
+}
+
+impl VisitorMut for Prefixer {
+    fn visit_type_mut(&mut self, t: &mut Type) {
+        self.within(t.name().to_owned(), |v| visit::walk_type_mut(v, t));
+    }
+
+    fn visit_property_mut(&mut self, property: &mut Property) {
+        self.within(util::pascal_case(property.name()), |v| {
+            visit::walk_property_mut(v, property)
+        });
+    }
+
+    fn visit_method_mut(&mut self, method: &mut Method) {
+        self.within(util::pascal_case(method.name()), |v| {
+            visit::walk_method_mut(v, method)
+        });
+    }
+
+    // An event's listener is only ever called `callback`, so its parameters are named
+    // after the event.
+    fn visit_event_mut(&mut self, event: &mut Event) {
+        self.within(util::pascal_case(event.name()), |v| {
+            visit::walk_method_mut(v, event.callback_mut());
+            for arg in event.extra_args_mut() {
+                v.visit_argument_mut(arg);
+            }
+        });
+    }
+
+    fn visit_argument_mut(&mut self, arg: &mut Argument) {
+        if let ArgumentKind::Element { element } = arg.kind_mut() {
+            let mut names = Counter::default();
+            names.visit_type_ref(element.type_ref());
+            let clashes = names
+                .counts
+                .keys()
+                .any(|name| self.clashing.iter().any(|c| c == name));
+            if clashes {
+                Renamer {
+                    prefix: self.scope.concat(),
+                }
+                .visit_type_ref_mut(element.type_ref_mut());
+            }
+        }
+        visit::walk_argument_mut(self, arg);
+    }
+}
+
+struct Renamer {
+    prefix: String,
+}
+
+impl VisitorMut for Renamer {
+    fn visit_type_mut(&mut self, t: &mut Type) {
+        t.rename(format!("{}{}", self.prefix, t.name()));
+        visit::walk_type_mut(self, t);
+    }
+}
@@ -0,0 +1,932 @@
+use super::{json, SchemaError, SchemaOptions};
+use crate::{api, markdown};
+use std::collections::HashMap;
+
+// Chromium's IDL dialect: one `namespace` block per file holding dictionaries, enums,
+// callback types and the `Functions`, `Events` and `Properties` interfaces. Items are
+// documented by the `//` comment block right before them, with `|name|: ...` lines
+// describing parameters.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(String),
+    Punct(char),
+}
+
+#[derive(Debug)]
+struct Lexed {
+    token: Token,
+    line: usize,
+    doc: Vec<String>,
+}
+
+fn lex(text: &str) -> Result<Vec<Lexed>, SchemaError> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens: Vec<Lexed> = vec![];
+    let mut doc = vec![];
+    let mut line = 1;
+    let mut newlines = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            newlines += 1;
+            // A blank line separates a comment from what follows, as with the license
+            // header.
+            if newlines > 1 {
+                doc.clear();
+            }
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |end| i + end);
+            let comment = chars[i + 2..end].iter().collect::<String>();
+            // Comments trailing a declaration on the same line don't document the next one.
+            if tokens.last().is_none_or(|t| t.line != line) {
+                let comment = comment.strip_prefix(' ').unwrap_or(&comment);
+                doc.push(comment.trim_end().to_owned());
+            }
+            newlines = 0;
+            i = end;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+
+        newlines = 0;
+        let start = i;
+        let token = if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || "_.".contains(chars[i])) {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            Token::Number(chars[start..i].iter().collect())
+        } else if c == '"' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&c| c == '"')
+                .ok_or_else(|| {
+                    SchemaError::invalid(format!("line {}: unterminated string", line))
+                })?;
+            i += end + 2;
+            Token::Str(chars[start + 1..i - 1].iter().collect())
+        } else {
+            i += 1;
+            Token::Punct(c)
+        };
+        tokens.push(Lexed {
+            token,
+            line,
+            doc: std::mem::take(&mut doc),
+        });
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Default)]
+struct Attributes(Vec<(String, Vec<String>)>);
+
+impl Attributes {
+    fn has(&self, name: &str) -> bool {
+        self.0.iter().any(|(n, _)| n == name)
+    }
+
+    fn values(&self, name: &str) -> &[String] {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map_or(&[], |(_, values)| values.as_slice())
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).first().map(String::as_str)
+    }
+}
+
+#[derive(Debug)]
+enum Ty {
+    Named(String),
+    Array(Box<Ty>),
+    Union(Vec<Ty>),
+    Promise(Box<Ty>),
+}
+
+#[derive(Debug)]
+struct Param {
+    attrs: Attributes,
+    optional: bool,
+    ty: Ty,
+    name: String,
+}
+
+#[derive(Debug)]
+struct Operation {
+    doc: Vec<String>,
+    attrs: Attributes,
+    returns: Ty,
+    name: String,
+    params: Vec<Param>,
+}
+
+#[derive(Debug)]
+struct Field {
+    doc: Vec<String>,
+    attrs: Attributes,
+    optional: bool,
+    ty: Ty,
+    name: String,
+}
+
+#[derive(Debug)]
+struct EnumValue {
+    doc: Vec<String>,
+    attrs: Attributes,
+    name: String,
+}
+
+#[derive(Debug)]
+enum TypeDecl {
+    Dictionary {
+        fields: Vec<Field>,
+        methods: Vec<Operation>,
+    },
+    Enum(Vec<EnumValue>),
+    Typedef,
+}
+
+#[derive(Debug)]
+struct Decl<T> {
+    doc: Vec<String>,
+    attrs: Attributes,
+    name: String,
+    kind: T,
+}
+
+#[derive(Debug)]
+struct Const {
+    ty: Ty,
+    value: Token,
+}
+
+#[derive(Debug, Default)]
+struct IdlNamespace {
+    doc: Vec<String>,
+    attrs: Attributes,
+    name: String,
+    types: Vec<Decl<TypeDecl>>,
+    callbacks: Vec<Decl<Vec<Param>>>,
+    consts: Vec<Decl<Const>>,
+    functions: Vec<Operation>,
+    events: Vec<Operation>,
+    properties: Vec<Operation>,
+}
+
+struct Parser {
+    tokens: Vec<Lexed>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|t| &t.token)
+    }
+
+    fn peek_punct(&self, c: char) -> bool {
+        self.peek_at(0) == Some(&Token::Punct(c))
+    }
+
+    fn peek_ident(&self, word: &str) -> bool {
+        matches!(self.peek_at(0), Some(Token::Ident(ident)) if ident == word)
+    }
+
+    fn doc(&self) -> Vec<String> {
+        self.tokens
+            .get(self.pos)
+            .map(|t| t.doc.clone())
+            .unwrap_or_default()
+    }
+
+    fn error(&self, expected: &str) -> SchemaError {
+        match self.tokens.get(self.pos) {
+            Some(t) => SchemaError::invalid(format!(
+                "line {}: expected {}, found {:?}",
+                t.line, expected, t.token
+            )),
+            None => SchemaError::invalid(format!("expected {}, found end of file", expected)),
+        }
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token, SchemaError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|t| t.token.clone())
+            .ok_or_else(|| self.error(expected))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn punct(&mut self, c: char) -> Result<(), SchemaError> {
+        if self.peek_punct(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("{:?}", c)))
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> Result<(), SchemaError> {
+        if self.peek_ident(word) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("{:?}", word)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, SchemaError> {
+        match self.peek_at(0) {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("an identifier")),
+        }
+    }
+
+    // `[nodoc, instanceOf=Blob, platforms=("chromeos", "win")]`
+    fn attributes(&mut self) -> Result<Attributes, SchemaError> {
+        let mut attrs = Attributes::default();
+        if !self.peek_punct('[') {
+            return Ok(attrs);
+        }
+        self.punct('[')?;
+        while !self.peek_punct(']') {
+            let name = self.ident()?;
+            let mut values = vec![];
+            if self.peek_punct('=') {
+                self.punct('=')?;
+                if self.peek_punct('(') {
+                    self.punct('(')?;
+                    while !self.peek_punct(')') {
+                        values.push(self.attribute_value()?);
+                        if !self.peek_punct(')') {
+                            self.punct(',')?;
+                        }
+                    }
+                    self.punct(')')?;
+                } else {
+                    values.push(self.attribute_value()?);
+                }
+            }
+            attrs.0.push((name, values));
+            if !self.peek_punct(']') {
+                self.punct(',')?;
+            }
+        }
+        self.punct(']')?;
+        Ok(attrs)
+    }
+
+    fn attribute_value(&mut self) -> Result<String, SchemaError> {
+        match self.next("an attribute value")? {
+            Token::Ident(value) | Token::Str(value) | Token::Number(value) => Ok(value),
+            Token::Punct(_) => {
+                self.pos -= 1;
+                Err(self.error("an attribute value"))
+            }
+        }
+    }
+
+    fn ty(&mut self) -> Result<(Ty, bool), SchemaError> {
+        let mut ty = if self.peek_punct('(') {
+            self.punct('(')?;
+            let mut alternatives = vec![self.ty()?.0];
+            while self.peek_ident("or") {
+                self.keyword("or")?;
+                alternatives.push(self.ty()?.0);
+            }
+            self.punct(')')?;
+            Ty::Union(alternatives)
+        } else {
+            let mut name = self.ident()?;
+            if name == "unsigned" {
+                name = self.ident()?;
+            }
+            if name == "long" && self.peek_ident("long") {
+                self.keyword("long")?;
+            }
+            if name == "Promise" && self.peek_punct('<') {
+                self.punct('<')?;
+                let resolved = self.ty()?.0;
+                self.punct('>')?;
+                Ty::Promise(Box::new(resolved))
+            } else {
+                Ty::Named(name)
+            }
+        };
+
+        let mut nullable = false;
+        loop {
+            if self.peek_punct('[') && self.peek_at(1) == Some(&Token::Punct(']')) {
+                self.pos += 2;
+                ty = Ty::Array(Box::new(ty));
+            } else if self.peek_punct('?') {
+                self.pos += 1;
+                nullable = true;
+            } else {
+                return Ok((ty, nullable));
+            }
+        }
+    }
+
+    fn params(&mut self) -> Result<Vec<Param>, SchemaError> {
+        let mut params = vec![];
+        self.punct('(')?;
+        while !self.peek_punct(')') {
+            let attrs = self.attributes()?;
+            let optional = self.peek_ident("optional");
+            if optional {
+                self.keyword("optional")?;
+            }
+            let (ty, nullable) = self.ty()?;
+            params.push(Param {
+                attrs,
+                optional: optional || nullable,
+                ty,
+                name: self.ident()?,
+            });
+            if !self.peek_punct(')') {
+                self.punct(',')?;
+            }
+        }
+        self.punct(')')?;
+        Ok(params)
+    }
+
+    // `static void get(DOMString name, AlarmCallback callback);`
+    fn operation(&mut self, doc: Vec<String>, attrs: Attributes) -> Result<Operation, SchemaError> {
+        if self.peek_ident("static") {
+            self.keyword("static")?;
+        }
+        let returns = self.ty()?.0;
+        let name = self.ident()?;
+        let params = self.params()?;
+        self.punct(';')?;
+        Ok(Operation {
+            doc,
+            attrs,
+            returns,
+            name,
+            params,
+        })
+    }
+
+    fn namespace(&mut self) -> Result<IdlNamespace, SchemaError> {
+        let doc = self.doc();
+        let attrs = self.attributes()?;
+        self.keyword("namespace")?;
+        let mut namespace = IdlNamespace {
+            doc,
+            attrs,
+            name: self.ident()?,
+            ..IdlNamespace::default()
+        };
+        self.punct('{')?;
+        while !self.peek_punct('}') {
+            self.member(&mut namespace)?;
+        }
+        self.punct('}')?;
+        if self.peek_punct(';') {
+            self.punct(';')?;
+        }
+        if self.pos < self.tokens.len() {
+            return Err(self.error("end of file"));
+        }
+        Ok(namespace)
+    }
+
+    fn member(&mut self, namespace: &mut IdlNamespace) -> Result<(), SchemaError> {
+        let doc = self.doc();
+        let attrs = self.attributes()?;
+        let keyword = self.ident()?;
+        match keyword.as_str() {
+            "dictionary" => {
+                let name = self.ident()?;
+                let mut fields = vec![];
+                let mut methods = vec![];
+                self.punct('{')?;
+                while !self.peek_punct('}') {
+                    let doc = self.doc();
+                    let attrs = self.attributes()?;
+                    if self.peek_ident("static") {
+                        methods.push(self.operation(doc, attrs)?);
+                        continue;
+                    }
+                    let (ty, optional) = self.ty()?;
+                    fields.push(Field {
+                        doc,
+                        attrs,
+                        optional,
+                        ty,
+                        name: self.ident()?,
+                    });
+                    self.punct(';')?;
+                }
+                self.punct('}')?;
+                self.punct(';')?;
+                namespace.types.push(Decl {
+                    doc,
+                    attrs,
+                    name,
+                    kind: TypeDecl::Dictionary { fields, methods },
+                });
+            }
+            "enum" => {
+                let name = self.ident()?;
+                let mut values = vec![];
+                self.punct('{')?;
+                while !self.peek_punct('}') {
+                    let doc = self.doc();
+                    let attrs = self.attributes()?;
+                    let name = match self.next("an enum value")? {
+                        Token::Ident(name) | Token::Str(name) => name,
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("an enum value"));
+                        }
+                    };
+                    values.push(EnumValue { doc, attrs, name });
+                    if !self.peek_punct('}') {
+                        self.punct(',')?;
+                    }
+                }
+                self.punct('}')?;
+                self.punct(';')?;
+                namespace.types.push(Decl {
+                    doc,
+                    attrs,
+                    name,
+                    kind: TypeDecl::Enum(values),
+                });
+            }
+            "typedef" => {
+                self.ty()?;
+                let name = self.ident()?;
+                self.punct(';')?;
+                namespace.types.push(Decl {
+                    doc,
+                    attrs,
+                    name,
+                    kind: TypeDecl::Typedef,
+                });
+            }
+            // `callback AlarmCallback = void (optional Alarm alarm);`
+            "callback" => {
+                let name = self.ident()?;
+                self.punct('=')?;
+                self.ty()?;
+                let params = self.params()?;
+                self.punct(';')?;
+                namespace.callbacks.push(Decl {
+                    doc,
+                    attrs,
+                    name,
+                    kind: params,
+                });
+            }
+            "const" => {
+                let ty = self.ty()?.0;
+                let name = self.ident()?;
+                self.punct('=')?;
+                let value = self.next("a constant")?;
+                self.punct(';')?;
+                namespace.consts.push(Decl {
+                    doc,
+                    attrs,
+                    name,
+                    kind: Const { ty, value },
+                });
+            }
+            "interface" => {
+                let interface = self.ident()?;
+                let mut operations = vec![];
+                self.punct('{')?;
+                while !self.peek_punct('}') {
+                    let doc = self.doc();
+                    let attrs = self.attributes()?;
+                    operations.push(self.operation(doc, attrs)?);
+                }
+                self.punct('}')?;
+                self.punct(';')?;
+                match interface.as_str() {
+                    "Functions" => namespace.functions = operations,
+                    "Events" => namespace.events = operations,
+                    "Properties" => namespace.properties = operations,
+                    _ => {
+                        return Err(SchemaError::invalid(format!(
+                            "unknown interface {:?}",
+                            interface
+                        )))
+                    }
+                }
+            }
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("a declaration"));
+            }
+        }
+        Ok(())
+    }
+}
+
+// A comment block split into the item's description and its `|name|: ...` lines.
+#[derive(Default)]
+struct Doc {
+    description: Option<String>,
+    params: HashMap<String, String>,
+}
+
+impl Doc {
+    fn parse(lines: &[String]) -> Doc {
+        let mut paragraphs: Vec<Vec<&str>> = vec![vec![]];
+        let mut params: Vec<(String, Vec<&str>)> = vec![];
+        let mut in_param = false;
+        for line in lines {
+            let line = line.trim();
+            if let Some((name, text)) = line.strip_prefix('|').and_then(|rest| rest.split_once('|'))
+            {
+                params.push((name.to_owned(), vec![text.trim_start_matches(':').trim()]));
+                in_param = true;
+            } else if line.is_empty() {
+                in_param = false;
+                paragraphs.push(vec![]);
+            } else if let (true, Some((_, text))) = (in_param, params.last_mut()) {
+                text.push(line);
+            } else {
+                paragraphs.last_mut().unwrap().push(line);
+            }
+        }
+
+        Doc {
+            description: to_markdown(paragraphs.iter().map(|p| p.join(" "))),
+            params: params
+                .into_iter()
+                .filter_map(|(name, text)| {
+                    Some((name, to_markdown(std::iter::once(text.join(" ")))?))
+                })
+                .collect(),
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<String> {
+        self.params.get(name).cloned()
+    }
+}
+
+fn to_markdown(paragraphs: impl Iterator<Item = String>) -> Option<String> {
+    let paragraphs = paragraphs
+        .filter_map(|p| markdown::from_html(&json::link_refs(&p)))
+        .collect::<Vec<_>>();
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("\n\n"))
+    }
+}
+
+fn availability(attrs: &Attributes) -> api::Availability {
+    let deprecated = if attrs.has("deprecated") {
+        Some(
+            to_markdown(attrs.value("deprecated").map(str::to_owned).into_iter())
+                .unwrap_or_default(),
+        )
+    } else {
+        None
+    };
+    api::Availability::new(
+        None,
+        api::Channel::Stable,
+        deprecated,
+        attrs.values("platforms").to_vec(),
+    )
+}
+
+struct Converter<'a> {
+    options: &'a SchemaOptions,
+    callbacks: HashMap<&'a str, &'a Decl<Vec<Param>>>,
+}
+
+impl<'a> Converter<'a> {
+    fn documented(&self, attrs: &Attributes) -> bool {
+        self.options.include_nodoc() || !attrs.has("nodoc")
+    }
+
+    fn namespace(&self, idl: &'a IdlNamespace) -> Result<api::Namespace, SchemaError> {
+        let types = idl
+            .types
+            .iter()
+            .filter(|t| self.documented(&t.attrs))
+            .map(|t| self.type_decl(t))
+            .collect::<Result<_, _>>()?;
+        let mut properties = idl
+            .consts
+            .iter()
+            .filter(|c| self.documented(&c.attrs))
+            .map(|c| self.constant(c))
+            .collect::<Result<Vec<_>, _>>()?;
+        for op in idl
+            .properties
+            .iter()
+            .filter(|op| self.documented(&op.attrs))
+        {
+            let type_ref = self.type_ref(&op.returns, &op.attrs)?;
+            let description = Doc::parse(&op.doc).description;
+            properties.push(
+                api::Property::new_immediate(op.name.clone(), type_ref, None, description)
+                    .with_availability(availability(&op.attrs)),
+            );
+        }
+        let methods = idl
+            .functions
+            .iter()
+            .filter(|op| self.documented(&op.attrs))
+            .map(|op| self.method(op))
+            .collect::<Result<_, _>>()?;
+        let events = idl
+            .events
+            .iter()
+            .filter(|op| self.documented(&op.attrs))
+            .map(|op| self.event(op))
+            .collect::<Result<_, _>>()?;
+
+        Ok(api::Namespace::new(
+            idl.name.clone(),
+            Doc::parse(&idl.doc).description,
+            types,
+            properties,
+            methods,
+            events,
+        )
+        .with_availability(availability(&idl.attrs)))
+    }
+
+    fn type_decl(&self, decl: &Decl<TypeDecl>) -> Result<api::Type, SchemaError> {
+        let description = Doc::parse(&decl.doc).description;
+        let t = match &decl.kind {
+            TypeDecl::Dictionary { fields, methods } => {
+                let mut elements = vec![];
+                let mut optional_elements = vec![];
+                for field in fields.iter().filter(|f| self.documented(&f.attrs)) {
+                    let element = self.element(
+                        &field.ty,
+                        &field.attrs,
+                        &field.name,
+                        Doc::parse(&field.doc).description,
+                    )?;
+                    if field.optional {
+                        optional_elements.push(element);
+                    } else {
+                        elements.push(element);
+                    }
+                }
+                let methods = methods
+                    .iter()
+                    .filter(|op| self.documented(&op.attrs))
+                    .map(|op| self.method(op))
+                    .collect::<Result<_, _>>()?;
+                api::Type::new_struct(
+                    decl.name.clone(),
+                    description,
+                    elements,
+                    optional_elements,
+                    methods,
+                    vec![],
+                )
+            }
+            TypeDecl::Enum(values) => {
+                let variants = values
+                    .iter()
+                    .filter(|v| self.documented(&v.attrs))
+                    .map(|v| api::EnumVariant::new(v.name.clone(), Doc::parse(&v.doc).description))
+                    .collect();
+                api::Type::new_enum(decl.name.clone(), description, variants)
+            }
+            TypeDecl::Typedef => api::Type::new_data(decl.name.clone(), description),
+        };
+        Ok(t.with_availability(availability(&decl.attrs)))
+    }
+
+    fn constant(&self, decl: &Decl<Const>) -> Result<api::Property, SchemaError> {
+        let invalid = || SchemaError::invalid(format!("invalid constant {:?}", decl.kind.value));
+        let value = match &decl.kind.value {
+            Token::Number(n) => match n.parse() {
+                Ok(i) => api::Literal::Integer(i),
+                Err(_) => n
+                    .parse()
+                    .ok()
+                    .and_then(api::Float::new)
+                    .map(api::Literal::Float)
+                    .ok_or_else(invalid)?,
+            },
+            Token::Str(s) => api::Literal::String(s.clone()),
+            _ => return Err(invalid()),
+        };
+        let type_ref = self.type_ref(&decl.kind.ty, &decl.attrs)?;
+        Ok(api::Property::new_immediate(
+            decl.name.clone(),
+            type_ref,
+            Some(value),
+            Doc::parse(&decl.doc).description,
+        )
+        .with_availability(availability(&decl.attrs)))
+    }
+
+    fn element(
+        &self,
+        ty: &Ty,
+        attrs: &Attributes,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<api::Element, SchemaError> {
+        let type_ref = self.type_ref(ty, attrs)?;
+        Ok(api::Element::new(type_ref, name.to_owned(), description)
+            .map_err(|e| SchemaError::invalid(e.to_string()))?
+            .with_availability(availability(attrs)))
+    }
+
+    // `[instanceOf=...]` names the class of an otherwise opaque `object`.
+    fn type_ref(&self, ty: &Ty, attrs: &Attributes) -> Result<api::TypeRef, SchemaError> {
+        match ty {
+            Ty::Named(name) => Ok(match name.as_str() {
+                "DOMString" => api::TypeRef::Primitive(api::Primitive::String),
+                "long" | "short" | "byte" | "octet" => {
+                    api::TypeRef::Primitive(api::Primitive::Integer)
+                }
+                "double" | "float" => api::TypeRef::Primitive(api::Primitive::Number),
+                "boolean" => api::TypeRef::Primitive(api::Primitive::Boolean),
+                "any" => api::TypeRef::Any,
+                "ArrayBuffer" => api::TypeRef::Binary,
+                "object" => match attrs.value("instanceOf") {
+                    Some(class) => api::TypeRef::Named(class.to_owned()),
+                    None => api::TypeRef::Object(None),
+                },
+                "void" => return Err(SchemaError::invalid("void is only valid as a return type")),
+                _ if self.callbacks.contains_key(name.as_str()) => api::TypeRef::Function,
+                _ => api::TypeRef::Named(name.clone()),
+            }),
+            Ty::Array(item) => Ok(api::TypeRef::Array(Box::new(self.type_ref(item, attrs)?))),
+            Ty::Union(alternatives) => alternatives
+                .iter()
+                .map(|t| self.type_ref(t, attrs))
+                .collect::<Result<_, _>>()
+                .map(api::TypeRef::union),
+            Ty::Promise(_) => Err(SchemaError::invalid(
+                "Promise is only valid as a return type",
+            )),
+        }
+    }
+
+    fn arguments(&self, params: &[Param], doc: &Doc) -> Result<Vec<api::Argument>, SchemaError> {
+        let mut args = vec![];
+        for param in params.iter().filter(|p| self.documented(&p.attrs)) {
+            let description = doc.param(&param.name);
+            let callback = match &param.ty {
+                Ty::Named(name) => self.callbacks.get(name.as_str()),
+                _ => None,
+            };
+            args.push(match callback {
+                Some(callback) => {
+                    let callback_doc = Doc::parse(&callback.doc);
+                    let method = api::Method::new(
+                        param.name.clone(),
+                        self.arguments(&callback.kind, &callback_doc)?,
+                        None,
+                        false,
+                        description.or(callback_doc.description),
+                    )
+                    .with_availability(availability(&callback.attrs));
+                    api::Argument::new_callback(method, param.optional)
+                }
+                None => api::Argument::new_element(
+                    self.element(&param.ty, &param.attrs, &param.name, description)?,
+                    param.optional,
+                ),
+            });
+        }
+        Ok(args)
+    }
+
+    // `[supportsPromises]` functions take a callback that may be left out to get a
+    // promise instead; `Promise<T>` return types say the same without a callback.
+    fn method(&self, op: &Operation) -> Result<api::Method, SchemaError> {
+        let doc = Doc::parse(&op.doc);
+        let mut args = self.arguments(&op.params, &doc)?;
+        let mut supports_promise = op.attrs.has("supportsPromises");
+        let returns = match &op.returns {
+            Ty::Named(name) if name == "void" => None,
+            Ty::Promise(resolved) => {
+                supports_promise = true;
+                let has_callback = args
+                    .iter()
+                    .any(|a| matches!(a.kind(), api::ArgumentKind::Callback { .. }));
+                if !has_callback {
+                    let callback_args = match &**resolved {
+                        Ty::Named(name) if name == "void" => vec![],
+                        resolved => vec![api::Argument::new_element(
+                            self.element(resolved, &op.attrs, "result", None)?,
+                            false,
+                        )],
+                    };
+                    let callback =
+                        api::Method::new("callback".to_owned(), callback_args, None, false, None);
+                    args.push(api::Argument::new_callback(callback, true));
+                }
+                None
+            }
+            returns => Some(api::Returns::new(self.type_ref(returns, &op.attrs)?, None)),
+        };
+
+        Ok(api::Method::new(
+            op.name.clone(),
+            args,
+            returns,
+            supports_promise,
+            doc.description,
+        )
+        .with_availability(availability(&op.attrs)))
+    }
+
+    fn event(&self, op: &Operation) -> Result<api::Event, SchemaError> {
+        let doc = Doc::parse(&op.doc);
+        let callback = api::Method::new(
+            "callback".to_owned(),
+            self.arguments(&op.params, &doc)?,
+            None,
+            false,
+            None,
+        );
+        let add_listener = api::Method::new(
+            "addListener".to_owned(),
+            vec![api::Argument::new_callback(callback, false)],
+            None,
+            false,
+            None,
+        );
+        Ok(
+            api::Event::new(op.name.clone(), add_listener, doc.description)
+                .with_availability(availability(&op.attrs)),
+        )
+    }
+}
+
+pub(crate) fn namespaces(
+    text: &str,
+    options: &SchemaOptions,
+) -> Result<Vec<api::Namespace>, SchemaError> {
+    let mut parser = Parser {
+        tokens: lex(text)?,
+        pos: 0,
+    };
+    let idl = parser.namespace()?;
+    if !options.include_nodoc() && idl.attrs.has("nodoc") {
+        return Ok(vec![]);
+    }
+
+    let converter = Converter {
+        options,
+        callbacks: idl.callbacks.iter().map(|c| (c.name.as_str(), c)).collect(),
+    };
+    let namespace = converter
+        .namespace(&idl)
+        .map_err(|e| e.in_namespace(&idl.name))?;
+    Ok(vec![namespace])
+}
@@ -0,0 +1,593 @@
+use super::{SchemaError, SchemaOptions};
+use crate::{api, markdown, util};
+use serde_json::{Map, Value};
+
+type Object = Map<String, Value>;
+
+// Firefox schemas can add to a type declared in another namespace or file with a
+// `"$extend": "Name"` entry, and copy a whole namespace with `"$import"`; those are
+// returned separately so the loader can apply them once every file has been read.
+pub(crate) struct Schema {
+    pub(crate) namespaces: Vec<api::Namespace>,
+    pub(crate) extensions: Vec<Extension>,
+    pub(crate) imports: Vec<Import>,
+}
+
+pub(crate) struct Extension {
+    pub(crate) namespace: String,
+    // Named after the type it extends.
+    pub(crate) body: api::Type,
+}
+
+pub(crate) struct Import {
+    pub(crate) namespace: String,
+    pub(crate) from: String,
+}
+
+pub(crate) fn namespaces(value: &Value, options: &SchemaOptions) -> Result<Schema, SchemaError> {
+    let entries = value
+        .as_array()
+        .ok_or_else(|| SchemaError::invalid("expected an array of namespaces"))?;
+    let mut converter = Converter {
+        options,
+        extensions: vec![],
+        imports: vec![],
+    };
+    let mut namespaces = vec![];
+    for object in converter.included(entries.iter(), "namespace")? {
+        let name = string(object, "namespace")
+            .ok_or_else(|| SchemaError::invalid("namespace without a name"))?;
+        let namespace = converter
+            .namespace(name, object)
+            .map_err(|e| e.in_namespace(name))?;
+        namespaces.push(namespace);
+    }
+
+    // Firefox has no per-namespace list of optional permissions; a file adds the ones
+    // its APIs may request at runtime to the manifest's `OptionalPermission` types.
+    let mut optional_permissions: Vec<String> = vec![];
+    for extension in &converter.extensions {
+        let target = extension.body.name();
+        let target = target.strip_prefix("manifest.").unwrap_or(target);
+        match extension.body.kind() {
+            api::TypeKind::Enum { variants } if target.starts_with("OptionalPermission") => {
+                for variant in variants {
+                    if !optional_permissions.iter().any(|p| p == variant.value()) {
+                        optional_permissions.push(variant.value().to_owned());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let namespaces = namespaces
+        .into_iter()
+        .map(|n| {
+            if n.name() == "manifest" {
+                return n;
+            }
+            let permissions = n.permissions().to_vec();
+            n.with_permissions(permissions, optional_permissions.clone())
+        })
+        .collect();
+
+    Ok(Schema {
+        namespaces,
+        extensions: converter.extensions,
+        imports: converter.imports,
+    })
+}
+
+struct Converter<'a> {
+    options: &'a SchemaOptions,
+    extensions: Vec<Extension>,
+    imports: Vec<Import>,
+}
+
+impl Converter<'_> {
+    fn included<'v>(
+        &self,
+        values: impl Iterator<Item = &'v Value>,
+        what: &str,
+    ) -> Result<Vec<&'v Object>, SchemaError> {
+        let mut objects = vec![];
+        for value in values {
+            let object = value
+                .as_object()
+                .ok_or_else(|| SchemaError::invalid(format!("{} is not an object", what)))?;
+            // `unsupported` marks Firefox items that exist only in Chrome.
+            if flag(object, "unsupported") {
+                continue;
+            }
+            if self.options.include_nodoc() || !flag(object, "nodoc") {
+                objects.push(object);
+            }
+        }
+        Ok(objects)
+    }
+
+    fn included_members<'v>(
+        &self,
+        object: &'v Object,
+        key: &str,
+    ) -> Result<Vec<(&'v str, &'v Object)>, SchemaError> {
+        let mut members = vec![];
+        for (name, value) in object
+            .get(key)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            for member in self.included(std::iter::once(value), name)? {
+                members.push((name.as_str(), member));
+            }
+        }
+        Ok(members)
+    }
+
+    fn namespace(&mut self, name: &str, object: &Object) -> Result<api::Namespace, SchemaError> {
+        if let Some(from) = string(object, "$import") {
+            self.imports.push(Import {
+                namespace: name.to_owned(),
+                from: from.to_owned(),
+            });
+        }
+        let mut types = vec![];
+        for t in self.included(list(object, "types"), "type")? {
+            if let Some(target) = string(t, "$extend") {
+                let body = self.type_body(target.to_owned(), None, t)?;
+                self.extensions.push(Extension {
+                    namespace: name.to_owned(),
+                    body,
+                });
+            } else {
+                types.push(self.type_def(t)?);
+            }
+        }
+        let properties = self
+            .included_members(object, "properties")?
+            .into_iter()
+            .map(|(name, p)| self.property(name, p))
+            .collect::<Result<_, _>>()?;
+        let methods = self
+            .included(list(object, "functions"), "function")?
+            .into_iter()
+            .map(|m| self.method(m, ""))
+            .collect::<Result<_, _>>()?;
+        let events = self
+            .included(list(object, "events"), "event")?
+            .into_iter()
+            .map(|e| self.event(e, ""))
+            .collect::<Result<_, _>>()?;
+        let mut manifest_keys: Vec<String> = object
+            .get("manifest_keys")
+            .and_then(Value::as_object)
+            .map(|keys| keys.keys().cloned().collect())
+            .unwrap_or_default();
+        // Firefox lists manifest keys alongside permissions as `manifest:key`.
+        let mut permissions = vec![];
+        for permission in list(object, "permissions").filter_map(Value::as_str) {
+            let (list, name) = match permission.strip_prefix("manifest:") {
+                Some(key) => (&mut manifest_keys, key),
+                None => (&mut permissions, permission),
+            };
+            if !list.iter().any(|n| n == name) {
+                list.push(name.to_owned());
+            }
+        }
+
+        Ok(api::Namespace::new(
+            name.to_owned(),
+            description(object),
+            types,
+            properties,
+            methods,
+            events,
+        )
+        .with_availability(availability(object))
+        .with_permissions(permissions, vec![])
+        .with_manifest_keys(manifest_keys))
+    }
+
+    fn type_def(&self, object: &Object) -> Result<api::Type, SchemaError> {
+        let name =
+            string(object, "id").ok_or_else(|| SchemaError::invalid("type without an id"))?;
+        let t = self.type_body(name.to_owned(), description(object), object)?;
+        Ok(t.with_availability(availability(object)))
+    }
+
+    fn type_body(
+        &self,
+        name: String,
+        description: Option<String>,
+        object: &Object,
+    ) -> Result<api::Type, SchemaError> {
+        if let Some(values) = object.get("enum") {
+            return Ok(api::Type::new_enum(
+                name,
+                description,
+                enum_variants(values)?,
+            ));
+        }
+        // Firefox splits enums whose values vary by version into string choices.
+        let choices: Vec<_> = list(object, "choices")
+            .filter_map(Value::as_object)
+            .collect();
+        if !choices.is_empty() && choices.iter().all(|c| c.get("enum").is_some()) {
+            let mut variants = vec![];
+            for choice in &choices {
+                variants.extend(enum_variants(&choice["enum"])?);
+            }
+            return Ok(api::Type::new_enum(name, description, variants));
+        }
+        // `$extend` entries leave out the `"type": "object"` of the type they extend.
+        if string(object, "type") == Some("object") || object.get("properties").is_some() {
+            self.struct_type(&name, description, object)
+        } else {
+            Ok(api::Type::new_data(name, description))
+        }
+    }
+
+    fn struct_type(
+        &self,
+        name: &str,
+        description: Option<String>,
+        object: &Object,
+    ) -> Result<api::Type, SchemaError> {
+        let mut elements = vec![];
+        let mut optional_elements = vec![];
+        for (field, value) in self.included_members(object, "properties")? {
+            let element = self.element(field, value, name)?;
+            if flag(value, "optional") {
+                optional_elements.push(element);
+            } else {
+                elements.push(element);
+            }
+        }
+        let methods = self
+            .included(list(object, "functions"), "function")?
+            .into_iter()
+            .map(|m| self.method(m, name))
+            .collect::<Result<_, _>>()?;
+        let events = self
+            .included(list(object, "events"), "event")?
+            .into_iter()
+            .map(|e| self.event(e, name))
+            .collect::<Result<_, _>>()?;
+        Ok(api::Type::new_struct(
+            name.to_owned(),
+            description,
+            elements,
+            optional_elements,
+            methods,
+            events,
+        ))
+    }
+
+    // Inline object types are named after their parent and field, as on the docs pages.
+    fn element(
+        &self,
+        name: &str,
+        object: &Object,
+        parent: &str,
+    ) -> Result<api::Element, SchemaError> {
+        let inline_name = format!("{}{}", parent, util::pascal_case(name));
+        let type_ref = self.type_ref(object, &inline_name)?;
+        Ok(
+            api::Element::new(type_ref, name.to_owned(), description(object))
+                .map_err(|e| SchemaError::invalid(e.to_string()))?
+                .with_availability(availability(object)),
+        )
+    }
+
+    fn type_ref(&self, object: &Object, inline_name: &str) -> Result<api::TypeRef, SchemaError> {
+        if let Some(name) = string(object, "$ref") {
+            return Ok(api::TypeRef::Named(name.to_owned()));
+        }
+        if let Some(choices) = object.get("choices") {
+            return self
+                .included(choices.as_array().into_iter().flatten(), "choice")?
+                .into_iter()
+                // A `null` choice only says the value may be missing.
+                .filter(|choice| string(choice, "type") != Some("null"))
+                .map(|choice| self.type_ref(choice, inline_name))
+                .collect::<Result<_, _>>()
+                .map(api::TypeRef::union);
+        }
+        if let Some(values) = object.get("enum") {
+            let values = enum_variants(values)?;
+            return Ok(api::TypeRef::Enum(
+                values.iter().map(|v| v.value().to_owned()).collect(),
+            ));
+        }
+
+        let type_name =
+            string(object, "type").ok_or_else(|| SchemaError::invalid("missing type"))?;
+        match type_name {
+            "string" => Ok(api::TypeRef::Primitive(api::Primitive::String)),
+            "integer" => Ok(api::TypeRef::Primitive(api::Primitive::Integer)),
+            "number" | "double" => Ok(api::TypeRef::Primitive(api::Primitive::Number)),
+            "boolean" => Ok(api::TypeRef::Primitive(api::Primitive::Boolean)),
+            "any" => Ok(api::TypeRef::Any),
+            "binary" => Ok(api::TypeRef::Binary),
+            "function" => Ok(api::TypeRef::Function),
+            "array" => {
+                let items = object
+                    .get("items")
+                    .and_then(Value::as_object)
+                    .ok_or_else(|| SchemaError::invalid("array without items"))?;
+                Ok(api::TypeRef::Array(Box::new(
+                    self.type_ref(items, inline_name)?,
+                )))
+            }
+            "object" => {
+                if let Some(name) = string(object, "isInstanceOf") {
+                    Ok(api::TypeRef::Named(name.to_owned()))
+                } else if ["properties", "functions", "events"]
+                    .iter()
+                    .any(|key| object.get(*key).is_some())
+                {
+                    let t = self.struct_type(inline_name, None, object)?;
+                    Ok(api::TypeRef::Object(Some(Box::new(t))))
+                } else {
+                    Ok(api::TypeRef::Object(None))
+                }
+            }
+            _ => Err(SchemaError::invalid(format!(
+                "unsupported type {:?}",
+                type_name
+            ))),
+        }
+    }
+
+    // Inline object types in parameters are named after the parameter alone; `parent`
+    // names the function, for what its callbacks return.
+    fn arguments(
+        &self,
+        object: &Object,
+        key: &str,
+        parent: &str,
+    ) -> Result<Vec<api::Argument>, SchemaError> {
+        self.included(list(object, key), "parameter")?
+            .into_iter()
+            .map(|p| self.argument(p, parent))
+            .collect()
+    }
+
+    fn argument(&self, object: &Object, parent: &str) -> Result<api::Argument, SchemaError> {
+        let name = string(object, "name")
+            .ok_or_else(|| SchemaError::invalid("parameter without a name"))?;
+        let optional = flag(object, "optional");
+        if string(object, "type") == Some("function") {
+            Ok(api::Argument::new_callback(
+                self.callback(name, object, parent)?,
+                optional,
+            ))
+        } else {
+            Ok(api::Argument::new_element(
+                self.element(name, object, "")?,
+                optional,
+            ))
+        }
+    }
+
+    fn callback(
+        &self,
+        name: &str,
+        object: &Object,
+        parent: &str,
+    ) -> Result<api::Method, SchemaError> {
+        let parent = format!("{}{}", parent, util::pascal_case(name));
+        Ok(api::Method::new(
+            name.to_owned(),
+            self.arguments(object, "parameters", &parent)?,
+            self.returns(object, &parent)?,
+            false,
+            description(object),
+        )
+        .with_availability(availability(object)))
+    }
+
+    fn returns(&self, object: &Object, parent: &str) -> Result<Option<api::Returns>, SchemaError> {
+        match object.get("returns").and_then(Value::as_object) {
+            Some(returns) => {
+                let type_ref = self.type_ref(returns, &format!("{}Returns", parent))?;
+                Ok(Some(api::Returns::new(type_ref, description(returns))))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // `returns_async` describes the callback of functions that also return a promise;
+    // older schemas list the callback as a plain last parameter instead.
+    fn method(&self, object: &Object, parent: &str) -> Result<api::Method, SchemaError> {
+        let name = string(object, "name")
+            .ok_or_else(|| SchemaError::invalid("function without a name"))?;
+        let parent = format!("{}{}", parent, util::pascal_case(name));
+        let mut args = self.arguments(object, "parameters", &parent)?;
+        let mut supports_promise = false;
+        if let Some(returns_async) = object.get("returns_async").and_then(Value::as_object) {
+            let callback_name = string(returns_async, "name").unwrap_or("callback");
+            let callback = self.callback(callback_name, returns_async, &parent)?;
+            args.push(api::Argument::new_callback(
+                callback,
+                flag(returns_async, "optional"),
+            ));
+            supports_promise = returns_async.get("does_not_support_promises").is_none();
+        }
+        // Firefox marks promise-returning functions with `"async"`, naming the
+        // callback parameter if there is one.
+        if flag(object, "async") {
+            supports_promise = true;
+        }
+
+        Ok(api::Method::new(
+            name.to_owned(),
+            args,
+            self.returns(object, &parent)?,
+            supports_promise,
+            description(object),
+        )
+        .with_availability(availability(object)))
+    }
+
+    fn event(&self, object: &Object, parent: &str) -> Result<api::Event, SchemaError> {
+        let name =
+            string(object, "name").ok_or_else(|| SchemaError::invalid("event without a name"))?;
+        let parent = format!("{}{}", parent, util::pascal_case(name));
+        let callback = api::Method::new(
+            "callback".to_owned(),
+            self.arguments(object, "parameters", &parent)?,
+            None,
+            false,
+            None,
+        );
+        let mut args = vec![api::Argument::new_callback(callback, false)];
+        args.extend(self.arguments(object, "extraParameters", &parent)?);
+        let add_listener = api::Method::new("addListener".to_owned(), args, None, false, None);
+        Ok(
+            api::Event::new(name.to_owned(), add_listener, description(object))
+                .with_availability(availability(object)),
+        )
+    }
+
+    fn property(&self, name: &str, object: &Object) -> Result<api::Property, SchemaError> {
+        let property = if let Some(value) = object.get("value") {
+            let value = literal(value)?;
+            let type_ref = if object.get("type").is_some() || object.get("$ref").is_some() {
+                self.type_ref(object, &util::pascal_case(name))?
+            } else {
+                value.type_ref()
+            };
+            api::Property::new_immediate(
+                name.to_owned(),
+                type_ref,
+                Some(value),
+                description(object),
+            )
+        } else if object.get("functions").is_some() && object.get("properties").is_none() {
+            let methods = self
+                .included(list(object, "functions"), "function")?
+                .into_iter()
+                .map(|m| self.method(m, &util::pascal_case(name)))
+                .collect::<Result<_, _>>()?;
+            api::Property::new_object(name.to_owned(), methods, description(object))
+        } else {
+            let type_ref = self.type_ref(object, &util::pascal_case(name))?;
+            api::Property::new_immediate(name.to_owned(), type_ref, None, description(object))
+        };
+        Ok(property.with_availability(availability(object)))
+    }
+}
+
+fn string<'a>(object: &'a Object, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(Value::as_str)
+}
+
+// Firefox sometimes gives a reason instead of `true`, e.g. `"optional": "omit-key-if-missing"`.
+fn flag(object: &Object, key: &str) -> bool {
+    match object.get(key) {
+        Some(Value::Bool(value)) => *value,
+        Some(Value::String(_)) => true,
+        _ => false,
+    }
+}
+
+fn list<'a>(object: &'a Object, key: &str) -> impl Iterator<Item = &'a Value> {
+    object
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+// Descriptions are HTML with `$(ref:name)` links to other items.
+fn description(object: &Object) -> Option<String> {
+    string(object, "description").and_then(|text| markdown::from_html(&link_refs(text)))
+}
+
+pub(crate) fn link_refs(text: &str) -> String {
+    let mut linked = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("$(ref:") {
+        let end = match rest[start..].find(')') {
+            Some(end) => start + end,
+            None => break,
+        };
+        linked.push_str(&rest[..start]);
+        linked.push_str("<code>");
+        linked.push_str(&rest[start + "$(ref:".len()..end]);
+        linked.push_str("</code>");
+        rest = &rest[end + 1..];
+    }
+    linked.push_str(rest);
+    linked
+}
+
+fn availability(object: &Object) -> api::Availability {
+    let deprecated = match object.get("deprecated") {
+        Some(Value::String(text)) => {
+            Some(markdown::from_html(&link_refs(text)).unwrap_or_default())
+        }
+        Some(Value::Bool(true)) => Some(String::new()),
+        _ => None,
+    };
+    let platforms = list(object, "platforms")
+        .filter_map(Value::as_str)
+        .map(str::to_owned)
+        .collect();
+    let contexts = list(object, "allowedContexts")
+        .filter_map(Value::as_str)
+        .map(str::to_owned)
+        .collect();
+    let manifest_version = |key| {
+        object
+            .get(key)
+            .and_then(Value::as_u64)
+            .map(|version| version as u32)
+    };
+    api::Availability::new(None, api::Channel::Stable, deprecated, platforms)
+        .with_contexts(contexts)
+        .with_manifest_versions(
+            manifest_version("min_manifest_version"),
+            manifest_version("max_manifest_version"),
+        )
+}
+
+// Enum values are either plain strings or `{ "name": ..., "description": ... }`.
+fn enum_variants(values: &Value) -> Result<Vec<api::EnumVariant>, SchemaError> {
+    let values = values
+        .as_array()
+        .ok_or_else(|| SchemaError::invalid("enum is not an array"))?;
+    values
+        .iter()
+        .map(|value| match value {
+            Value::String(name) => Ok(api::EnumVariant::new(name.clone(), None)),
+            Value::Number(n) => Ok(api::EnumVariant::new(n.to_string(), None)),
+            Value::Object(object) => {
+                let name = string(object, "name")
+                    .ok_or_else(|| SchemaError::invalid("enum value without a name"))?;
+                Ok(api::EnumVariant::new(name.to_owned(), description(object)))
+            }
+            _ => Err(SchemaError::invalid(format!(
+                "invalid enum value {}",
+                value
+            ))),
+        })
+        .collect()
+}
+
+fn literal(value: &Value) -> Result<api::Literal, SchemaError> {
+    match value {
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Ok(api::Literal::Integer(i)),
+            None => n
+                .as_f64()
+                .and_then(api::Float::new)
+                .map(api::Literal::Float)
+                .ok_or_else(|| SchemaError::invalid(format!("invalid constant {}", n))),
+        },
+        Value::String(s) => Ok(api::Literal::String(s.clone())),
+        _ => Err(SchemaError::invalid(format!("invalid constant {}", value))),
+    }
+}
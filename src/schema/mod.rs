@@ -0,0 +1,409 @@
+pub(crate) mod idl;
+pub(crate) mod json;
+
+use crate::api::{Namespace, Type, TypeKind};
+use crate::naming;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum SchemaErrorKind {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for SchemaErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaErrorKind::Io(error) => write!(f, "{}", error),
+            SchemaErrorKind::Json(error) => write!(f, "invalid JSON: {}", error),
+            SchemaErrorKind::Invalid(message) => f.write_str(message),
+        }
+    }
+}
+
+// Like `ParseError`, the file and namespace are filled in on the way up.
+#[derive(Debug)]
+pub struct SchemaError {
+    kind: SchemaErrorKind,
+    file: Option<PathBuf>,
+    namespace: Option<String>,
+}
+
+impl SchemaError {
+    pub(crate) fn new(kind: SchemaErrorKind) -> Self {
+        SchemaError {
+            kind,
+            file: None,
+            namespace: None,
+        }
+    }
+
+    pub(crate) fn invalid(message: impl Into<String>) -> Self {
+        SchemaError::new(SchemaErrorKind::Invalid(message.into()))
+    }
+
+    pub(crate) fn in_file(mut self, file: &Path) -> Self {
+        self.file.get_or_insert_with(|| file.to_owned());
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace: &str) -> Self {
+        self.namespace.get_or_insert_with(|| namespace.to_owned());
+        self
+    }
+
+    pub fn kind(&self) -> &SchemaErrorKind {
+        &self.kind
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+        if let Some(namespace) = &self.namespace {
+            write!(f, "{}: ", namespace)?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SchemaOptions {
+    include_nodoc: bool,
+    strict: bool,
+}
+
+impl SchemaOptions {
+    // Items marked `nodoc` are left out by default, as they are on the docs site.
+    pub fn with_nodoc(mut self, include_nodoc: bool) -> Self {
+        self.include_nodoc = include_nodoc;
+        self
+    }
+
+    pub fn include_nodoc(&self) -> bool {
+        self.include_nodoc
+    }
+
+    // In strict mode a `$extend` or `$import` that can't be applied fails the load
+    // instead of being reported next to the namespaces.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+}
+
+// Loads every `*.json` and `*.idl` schema in a directory such as Chromium's
+// `extensions/common/api`, in file name order. Files starting with `_` hold feature
+// definitions rather than APIs and are skipped.
+//
+// Like all the loaders, also returns the `$extend` and `$import` entries that couldn't
+// be applied, unless the options are strict.
+pub fn load_dir(
+    dir: &Path,
+    options: &SchemaOptions,
+) -> Result<(Vec<Namespace>, Vec<SchemaError>), SchemaError> {
+    load_dirs(&[dir], options)
+}
+
+// Loads several directories as one set of APIs, as Firefox needs for its
+// `toolkit/components/extensions/schemas` and `browser/components/extensions/schemas`.
+// Namespaces spread over several files are merged, and `$extend` entries are applied
+// to the types they name once everything has been read.
+pub fn load_dirs(
+    dirs: &[&Path],
+    options: &SchemaOptions,
+) -> Result<(Vec<Namespace>, Vec<SchemaError>), SchemaError> {
+    load_dirs_with_base(dirs, &mut [], options)
+}
+
+// Loads APIs built on top of an already loaded set, such as Thunderbird's
+// `mail/components/extensions/schemas` over Firefox's toolkit schemas. Only the new
+// namespaces are returned; their `$extend` and `$import` entries may target `base`,
+// and a namespace here shadows the one with the same name in `base` (Thunderbird has
+// its own `tabs` and `windows`). Link the result with
+// [`link_with`](crate::link::link_with) to resolve references into `base`.
+//
+// A `$extend` can't be applied when its target is missing (e.g. left out as
+// `unsupported`), ambiguous or of a different kind, and an `$import` when either
+// namespace is missing.
+pub fn load_dirs_with_base(
+    dirs: &[&Path],
+    base: &mut [Namespace],
+    options: &SchemaOptions,
+) -> Result<(Vec<Namespace>, Vec<SchemaError>), SchemaError> {
+    let mut schemas = vec![];
+    for dir in dirs {
+        let io_error = |e| SchemaError::new(SchemaErrorKind::Io(e)).in_file(dir);
+        let mut files = vec![];
+        for entry in fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            let is_schema = path.extension().is_some_and(|e| e == "json" || e == "idl")
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| !n.starts_with('_'));
+            if is_schema {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        for file in files {
+            schemas.push(read_file(&file, options)?);
+        }
+    }
+    combine(schemas, base, options)
+}
+
+pub fn load_file(
+    file: &Path,
+    options: &SchemaOptions,
+) -> Result<(Vec<Namespace>, Vec<SchemaError>), SchemaError> {
+    combine(vec![read_file(file, options)?], &mut [], options)
+}
+
+// A schema file is a JSON array of namespaces, with `//` and `/* */` comments allowed.
+pub fn parse_schema(
+    text: &str,
+    options: &SchemaOptions,
+) -> Result<(Vec<Namespace>, Vec<SchemaError>), SchemaError> {
+    combine(vec![parse_json(text, options)?], &mut [], options)
+}
+
+// An IDL file holds a single namespace; the result is empty when it is `[nodoc]`.
+pub fn parse_idl(text: &str, options: &SchemaOptions) -> Result<Vec<Namespace>, SchemaError> {
+    idl::namespaces(text, options)
+}
+
+fn read_file(file: &Path, options: &SchemaOptions) -> Result<json::Schema, SchemaError> {
+    let text = fs::read_to_string(file)
+        .map_err(|e| SchemaError::new(SchemaErrorKind::Io(e)).in_file(file))?;
+    let schema = if file.extension().is_some_and(|e| e == "idl") {
+        parse_idl(&text, options).map(|namespaces| json::Schema {
+            namespaces,
+            extensions: vec![],
+            imports: vec![],
+        })
+    } else {
+        parse_json(&text, options)
+    };
+    schema.map_err(|e| e.in_file(file))
+}
+
+fn parse_json(text: &str, options: &SchemaOptions) -> Result<json::Schema, SchemaError> {
+    let value = serde_json::from_str(&strip_comments(text))
+        .map_err(|e| SchemaError::new(SchemaErrorKind::Json(e)))?;
+    json::namespaces(&value, options)
+}
+
+fn combine(
+    schemas: Vec<json::Schema>,
+    base: &mut [Namespace],
+    options: &SchemaOptions,
+) -> Result<(Vec<Namespace>, Vec<SchemaError>), SchemaError> {
+    let mut namespaces: Vec<Namespace> = vec![];
+    let mut extensions = vec![];
+    let mut imports = vec![];
+    for schema in schemas {
+        for namespace in schema.namespaces {
+            match namespaces.iter_mut().find(|n| n.name() == namespace.name()) {
+                Some(existing) => existing.merge(namespace),
+                None => namespaces.push(namespace),
+            }
+        }
+        extensions.extend(schema.extensions);
+        imports.extend(schema.imports);
+    }
+
+    let mut diagnostics = vec![];
+    let mut report = |e: SchemaError| {
+        if options.strict {
+            Err(e)
+        } else {
+            diagnostics.push(e);
+            Ok(())
+        }
+    };
+    for import in imports {
+        let from = namespaces
+            .iter()
+            .chain(base.iter())
+            .find(|n| n.name() == import.from)
+            .cloned();
+        let namespace = namespaces.iter_mut().find(|n| n.name() == import.namespace);
+        match (namespace, from) {
+            (Some(namespace), Some(from)) => namespace.import(&from),
+            (None, _) => report(
+                SchemaError::invalid(format!("no namespace {} to import into", import.namespace))
+                    .in_namespace(&import.namespace),
+            )?,
+            (_, None) => report(
+                SchemaError::invalid(format!("no namespace {} to import", import.from))
+                    .in_namespace(&import.namespace),
+            )?,
+        }
+    }
+
+    for extension in extensions {
+        let target = extension.body.name().to_owned();
+        let applied = match find_type(&mut namespaces, base, &extension.namespace, &target) {
+            Ok(t) => {
+                let kind = kind_name(t);
+                let extension_kind = kind_name(&extension.body);
+                if t.extend(extension.body) {
+                    Ok(())
+                } else {
+                    Err(SchemaError::invalid(format!(
+                        "cannot extend {} {} with a {}",
+                        kind, target, extension_kind
+                    )))
+                }
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = applied {
+            report(e.in_namespace(&extension.namespace))?;
+        }
+    }
+
+    // Entries such as `"namespace": "manifest"` that only held `$extend`s for `base`
+    // leave an empty namespace behind.
+    namespaces.retain(|n| {
+        let is_empty = n.description().is_none()
+            && n.types().is_empty()
+            && n.properties().is_empty()
+            && n.methods().is_empty()
+            && n.events().is_empty();
+        !is_empty || base.iter().all(|b| b.name() != n.name())
+    });
+    for namespace in &mut namespaces {
+        naming::disambiguate(namespace);
+    }
+    Ok((namespaces, diagnostics))
+}
+
+// Finds the type a `$extend` in `namespace` targets, looking in the new namespaces
+// before `base`. A qualified `namespace.Type` must be exactly there; otherwise the
+// extending namespace comes first, then the one namespace anywhere with such a type.
+fn find_type<'a>(
+    namespaces: &'a mut [Namespace],
+    base: &'a mut [Namespace],
+    namespace: &str,
+    target: &str,
+) -> Result<&'a mut Type, SchemaError> {
+    let (namespace, name, qualified) = match target.rsplit_once('.') {
+        Some((namespace, name)) => (namespace, name, true),
+        None => (namespace, target, false),
+    };
+    let has_type = |n: &Namespace| n.types().iter().any(|t| t.name() == name);
+    // Positions as `(in_base, index)`.
+    let positions = |matches: &dyn Fn(&Namespace) -> bool| {
+        let own = namespaces.iter().enumerate().map(|(i, n)| (false, i, n));
+        let shared = base.iter().enumerate().map(|(i, n)| (true, i, n));
+        own.chain(shared)
+            .filter(|(_, _, n)| matches(n))
+            .map(|(in_base, i, n)| (in_base, i, n.name().to_owned()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut found = positions(&|n| n.name() == namespace && has_type(n));
+    if found.is_empty() && !qualified {
+        found = positions(&has_type);
+        if found.len() > 1 {
+            let candidates = found
+                .iter()
+                .map(|(_, _, n)| format!("{}.{}", n, name))
+                .collect::<Vec<_>>();
+            return Err(SchemaError::invalid(format!(
+                "ambiguous type {} to extend, candidates are {}",
+                target,
+                candidates.join(", ")
+            )));
+        }
+    }
+    let (in_base, index, _) = found
+        .into_iter()
+        .next()
+        .ok_or_else(|| SchemaError::invalid(format!("no type {} to extend", target)))?;
+    let namespaces = if in_base { base } else { namespaces };
+    namespaces[index]
+        .types_mut()
+        .iter_mut()
+        .find(|t| t.name() == name)
+        .ok_or_else(|| SchemaError::invalid(format!("no type {} to extend", target)))
+}
+
+fn kind_name(t: &Type) -> &'static str {
+    match t.kind() {
+        TypeKind::Enum { .. } => "enum",
+        TypeKind::Data => "data type",
+        TypeKind::Struct { .. } => "struct",
+    }
+}
+
+// Comments are replaced by whitespace, keeping line numbers in JSON errors intact.
+fn strip_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(c);
+            }
+            ('/', Some('/')) => {
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push('\n');
+                    }
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            _ => stripped.push(c),
+        }
+    }
+    stripped
+}
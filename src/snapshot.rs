@@ -0,0 +1,71 @@
+//! Versioned JSON snapshots of parsed namespaces.
+//!
+//! A snapshot is a single JSON object:
+//!
+//! ```json
+//! { "version": 2, "namespaces": [ ... ] }
+//! ```
+//!
+//! Each entry of `namespaces` is an [`api::Namespace`](crate::api::Namespace) serialized
+//! field by field, with the Rust field names as keys. Enums such as `TypeRef`,
+//! `TypeKind` or `Literal` are written as `{ "Variant": content }`, unit variants
+//! as plain strings (`"Stable"`, `"Function"`), and absent optional values as `null`.
+//!
+//! `version` is bumped whenever the layout changes incompatibly; loading a snapshot
+//! with a different version fails instead of guessing.
+
+use crate::api::Namespace;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+pub const VERSION: u32 = 2;
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    namespaces: Vec<Namespace>,
+}
+
+impl Snapshot {
+    pub fn new(namespaces: Vec<Namespace>) -> Snapshot {
+        Snapshot {
+            version: VERSION,
+            namespaces,
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn namespaces(&self) -> &[Namespace] {
+        &self.namespaces
+    }
+
+    pub fn into_namespaces(self) -> Vec<Namespace> {
+        self.namespaces
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // The version is checked before the namespaces are read, as an older layout
+    // would fail to deserialize with a less helpful error.
+    pub fn from_json(json: &str) -> Result<Snapshot, Box<dyn Error>> {
+        let header: Header = serde_json::from_str(json)?;
+        if header.version != VERSION {
+            return Err(format!(
+                "unsupported snapshot version {} (expected {})",
+                header.version, VERSION
+            )
+            .into());
+        }
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
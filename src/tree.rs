@@ -0,0 +1,166 @@
+use crate::api::Namespace;
+
+#[derive(Debug)]
+struct Node {
+    name: String,
+    path: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    namespace: Option<Namespace>,
+}
+
+// Namespaces nested by their dotted names, so that `system.cpu` and `system.memory`
+// become children of a `system` node even though there is no `system` API.
+#[derive(Debug)]
+pub struct NamespaceTree {
+    global: String,
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NamespaceNode<'a> {
+    tree: &'a NamespaceTree,
+    index: usize,
+}
+
+impl NamespaceTree {
+    pub fn new(global: &str) -> Self {
+        NamespaceTree {
+            global: global.to_owned(),
+            nodes: vec![Node {
+                name: String::new(),
+                path: String::new(),
+                parent: None,
+                children: vec![],
+                namespace: None,
+            }],
+        }
+    }
+
+    pub fn from_namespaces(global: &str, namespaces: Vec<Namespace>) -> Self {
+        let mut tree = NamespaceTree::new(global);
+        for namespace in namespaces {
+            tree.insert(namespace);
+        }
+        tree
+    }
+
+    pub fn global(&self) -> &str {
+        &self.global
+    }
+
+    // Returns the namespace previously stored under the same name, if any.
+    pub fn insert(&mut self, namespace: Namespace) -> Option<Namespace> {
+        let index = self.insert_path(namespace.name());
+        self.nodes[index].namespace.replace(namespace)
+    }
+
+    fn insert_path(&mut self, path: &str) -> usize {
+        let mut index = 0;
+        for segment in path.split('.') {
+            let existing = self.nodes[index]
+                .children
+                .iter()
+                .copied()
+                .find(|&child| self.nodes[child].name == segment);
+            index = match existing {
+                Some(child) => child,
+                None => {
+                    let parent = &self.nodes[index];
+                    let path = if parent.path.is_empty() {
+                        segment.to_owned()
+                    } else {
+                        format!("{}.{}", parent.path, segment)
+                    };
+                    self.nodes.push(Node {
+                        name: segment.to_owned(),
+                        path,
+                        parent: Some(index),
+                        children: vec![],
+                        namespace: None,
+                    });
+                    let child = self.nodes.len() - 1;
+                    self.nodes[index].children.push(child);
+                    child
+                }
+            };
+        }
+        index
+    }
+
+    pub fn root(&self) -> NamespaceNode<'_> {
+        NamespaceNode {
+            tree: self,
+            index: 0,
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<NamespaceNode<'_>> {
+        path.split('.')
+            .try_fold(self.root(), |node, segment| node.child(segment))
+    }
+
+    // All nodes below the root in depth-first order, parents before children.
+    pub fn iter(&self) -> impl Iterator<Item = NamespaceNode<'_>> {
+        let mut stack = vec![self.root()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children().rev());
+            Some(node)
+        })
+        .skip(1)
+    }
+
+    pub fn namespaces(&self) -> impl Iterator<Item = &Namespace> {
+        self.iter().filter_map(|node| node.namespace())
+    }
+}
+
+impl<'a> NamespaceNode<'a> {
+    fn node(&self) -> &'a Node {
+        &self.tree.nodes[self.index]
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.index == 0
+    }
+
+    pub fn name(&self) -> &'a str {
+        &self.node().name
+    }
+
+    pub fn path(&self) -> &'a str {
+        &self.node().path
+    }
+
+    pub fn js_path(&self) -> String {
+        if self.is_root() {
+            self.tree.global.clone()
+        } else {
+            format!("{}.{}", self.tree.global, self.path())
+        }
+    }
+
+    pub fn namespace(&self) -> Option<&'a Namespace> {
+        self.node().namespace.as_ref()
+    }
+
+    pub fn parent(&self) -> Option<NamespaceNode<'a>> {
+        self.node().parent.map(|index| NamespaceNode {
+            tree: self.tree,
+            index,
+        })
+    }
+
+    pub fn children(&self) -> impl DoubleEndedIterator<Item = NamespaceNode<'a>> {
+        let tree = self.tree;
+        self.node()
+            .children
+            .iter()
+            .map(move |&index| NamespaceNode { tree, index })
+    }
+
+    pub fn child(&self, name: &str) -> Option<NamespaceNode<'a>> {
+        self.children().find(|child| child.name() == name)
+    }
+}
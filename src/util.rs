@@ -41,7 +41,7 @@ pub(crate) enum TakeResult<T> {
 pub(crate) fn take_one<T>(iter: impl Iterator<Item = T>) -> TakeResult<T> {
     let mut v = iter.collect::<Vec<_>>();
 
-    if v.len() == 0 {
+    if v.is_empty() {
         TakeResult::Zero
     } else if v.len() == 1 {
         TakeResult::One(v.remove(0))
@@ -49,3 +49,16 @@ pub(crate) fn take_one<T>(iter: impl Iterator<Item = T>) -> TakeResult<T> {
         TakeResult::More
     }
 }
+
+pub(crate) fn pascal_case(name: &str) -> String {
+    name.split(['_', '-', '.'])
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
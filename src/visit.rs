@@ -0,0 +1,275 @@
+use crate::api::*;
+
+pub trait Visitor<'ast> {
+    fn visit_namespace(&mut self, namespace: &'ast Namespace) {
+        walk_namespace(self, namespace)
+    }
+
+    fn visit_type(&mut self, t: &'ast Type) {
+        walk_type(self, t)
+    }
+
+    fn visit_enum_variant(&mut self, _variant: &'ast EnumVariant) {}
+
+    fn visit_element(&mut self, element: &'ast Element) {
+        walk_element(self, element)
+    }
+
+    fn visit_type_ref(&mut self, type_ref: &'ast TypeRef) {
+        walk_type_ref(self, type_ref)
+    }
+
+    fn visit_property(&mut self, property: &'ast Property) {
+        walk_property(self, property)
+    }
+
+    fn visit_method(&mut self, method: &'ast Method) {
+        walk_method(self, method)
+    }
+
+    fn visit_argument(&mut self, arg: &'ast Argument) {
+        walk_argument(self, arg)
+    }
+
+    fn visit_returns(&mut self, returns: &'ast Returns) {
+        walk_returns(self, returns)
+    }
+
+    fn visit_event(&mut self, event: &'ast Event) {
+        walk_event(self, event)
+    }
+}
+
+pub fn walk_namespace<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, namespace: &'ast Namespace) {
+    for t in namespace.types() {
+        v.visit_type(t);
+    }
+    for property in namespace.properties() {
+        v.visit_property(property);
+    }
+    for method in namespace.methods() {
+        v.visit_method(method);
+    }
+    for event in namespace.events() {
+        v.visit_event(event);
+    }
+}
+
+pub fn walk_type<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, t: &'ast Type) {
+    match t.kind() {
+        TypeKind::Enum { variants } => {
+            for variant in variants {
+                v.visit_enum_variant(variant);
+            }
+        }
+        TypeKind::Data => {}
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            methods,
+            events,
+        } => {
+            for element in elements.iter().chain(optional_elements) {
+                v.visit_element(element);
+            }
+            for method in methods {
+                v.visit_method(method);
+            }
+            for event in events {
+                v.visit_event(event);
+            }
+        }
+    }
+}
+
+pub fn walk_element<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, element: &'ast Element) {
+    v.visit_type_ref(element.type_ref());
+}
+
+pub fn walk_type_ref<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, type_ref: &'ast TypeRef) {
+    match type_ref {
+        TypeRef::Array(item) => v.visit_type_ref(item),
+        TypeRef::Union(alternatives) => {
+            for alternative in alternatives {
+                v.visit_type_ref(alternative);
+            }
+        }
+        TypeRef::Object(Some(t)) => v.visit_type(t),
+        _ => {}
+    }
+}
+
+pub fn walk_property<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, property: &'ast Property) {
+    match property.kind() {
+        PropertyKind::Immediate { type_ref, .. } => v.visit_type_ref(type_ref),
+        PropertyKind::Object { methods } => {
+            for method in methods {
+                v.visit_method(method);
+            }
+        }
+    }
+}
+
+pub fn walk_method<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, method: &'ast Method) {
+    for arg in method.args() {
+        v.visit_argument(arg);
+    }
+    if let Some(returns) = method.returns() {
+        v.visit_returns(returns);
+    }
+}
+
+pub fn walk_argument<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, arg: &'ast Argument) {
+    match arg.kind() {
+        ArgumentKind::Element { element } => v.visit_element(element),
+        ArgumentKind::Callback { callback } => v.visit_method(callback),
+    }
+}
+
+pub fn walk_returns<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, returns: &'ast Returns) {
+    v.visit_type_ref(returns.type_ref());
+}
+
+pub fn walk_event<'ast, V: Visitor<'ast> + ?Sized>(v: &mut V, event: &'ast Event) {
+    v.visit_method(event.callback());
+    for arg in event.extra_args() {
+        v.visit_argument(arg);
+    }
+}
+
+pub trait VisitorMut {
+    fn visit_namespace_mut(&mut self, namespace: &mut Namespace) {
+        walk_namespace_mut(self, namespace)
+    }
+
+    fn visit_type_mut(&mut self, t: &mut Type) {
+        walk_type_mut(self, t)
+    }
+
+    fn visit_enum_variant_mut(&mut self, _variant: &mut EnumVariant) {}
+
+    fn visit_element_mut(&mut self, element: &mut Element) {
+        walk_element_mut(self, element)
+    }
+
+    fn visit_type_ref_mut(&mut self, type_ref: &mut TypeRef) {
+        walk_type_ref_mut(self, type_ref)
+    }
+
+    fn visit_property_mut(&mut self, property: &mut Property) {
+        walk_property_mut(self, property)
+    }
+
+    fn visit_method_mut(&mut self, method: &mut Method) {
+        walk_method_mut(self, method)
+    }
+
+    fn visit_argument_mut(&mut self, arg: &mut Argument) {
+        walk_argument_mut(self, arg)
+    }
+
+    fn visit_returns_mut(&mut self, returns: &mut Returns) {
+        walk_returns_mut(self, returns)
+    }
+
+    fn visit_event_mut(&mut self, event: &mut Event) {
+        walk_event_mut(self, event)
+    }
+}
+
+pub fn walk_namespace_mut<V: VisitorMut + ?Sized>(v: &mut V, namespace: &mut Namespace) {
+    for t in namespace.types_mut() {
+        v.visit_type_mut(t);
+    }
+    for property in namespace.properties_mut() {
+        v.visit_property_mut(property);
+    }
+    for method in namespace.methods_mut() {
+        v.visit_method_mut(method);
+    }
+    for event in namespace.events_mut() {
+        v.visit_event_mut(event);
+    }
+}
+
+pub fn walk_type_mut<V: VisitorMut + ?Sized>(v: &mut V, t: &mut Type) {
+    match t.kind_mut() {
+        TypeKind::Enum { variants } => {
+            for variant in variants {
+                v.visit_enum_variant_mut(variant);
+            }
+        }
+        TypeKind::Data => {}
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            methods,
+            events,
+        } => {
+            for element in elements.iter_mut().chain(optional_elements) {
+                v.visit_element_mut(element);
+            }
+            for method in methods {
+                v.visit_method_mut(method);
+            }
+            for event in events {
+                v.visit_event_mut(event);
+            }
+        }
+    }
+}
+
+pub fn walk_element_mut<V: VisitorMut + ?Sized>(v: &mut V, element: &mut Element) {
+    v.visit_type_ref_mut(element.type_ref_mut());
+}
+
+pub fn walk_type_ref_mut<V: VisitorMut + ?Sized>(v: &mut V, type_ref: &mut TypeRef) {
+    match type_ref {
+        TypeRef::Array(item) => v.visit_type_ref_mut(item),
+        TypeRef::Union(alternatives) => {
+            for alternative in alternatives {
+                v.visit_type_ref_mut(alternative);
+            }
+        }
+        TypeRef::Object(Some(t)) => v.visit_type_mut(t),
+        _ => {}
+    }
+}
+
+pub fn walk_property_mut<V: VisitorMut + ?Sized>(v: &mut V, property: &mut Property) {
+    match property.kind_mut() {
+        PropertyKind::Immediate { type_ref, .. } => v.visit_type_ref_mut(type_ref),
+        PropertyKind::Object { methods } => {
+            for method in methods {
+                v.visit_method_mut(method);
+            }
+        }
+    }
+}
+
+pub fn walk_method_mut<V: VisitorMut + ?Sized>(v: &mut V, method: &mut Method) {
+    for arg in method.args_mut() {
+        v.visit_argument_mut(arg);
+    }
+    if let Some(returns) = method.returns_mut() {
+        v.visit_returns_mut(returns);
+    }
+}
+
+pub fn walk_argument_mut<V: VisitorMut + ?Sized>(v: &mut V, arg: &mut Argument) {
+    match arg.kind_mut() {
+        ArgumentKind::Element { element } => v.visit_element_mut(element),
+        ArgumentKind::Callback { callback } => v.visit_method_mut(callback),
+    }
+}
+
+pub fn walk_returns_mut<V: VisitorMut + ?Sized>(v: &mut V, returns: &mut Returns) {
+    v.visit_type_ref_mut(returns.type_ref_mut());
+}
+
+pub fn walk_event_mut<V: VisitorMut + ?Sized>(v: &mut V, event: &mut Event) {
+    v.visit_method_mut(event.callback_mut());
+    for arg in event.extra_args_mut() {
+        v.visit_argument_mut(arg);
+    }
+}
@@ -0,0 +1,126 @@
+use webext_parser::api::{Primitive, TypeKind, TypeRef};
+use webext_parser::builder::{
+    element, BuildError, EventBuilder, MethodBuilder, NamespaceBuilder, PropertyBuilder,
+    TypeBuilder,
+};
+
+fn string() -> TypeRef {
+    TypeRef::Primitive(Primitive::String)
+}
+
+#[test]
+fn builds_namespace() {
+    let tab = TypeBuilder::new_struct("Tab")
+        .element(element("id", TypeRef::Primitive(Primitive::Integer)).unwrap())
+        .optional_element(element("url", string()).unwrap())
+        .build()
+        .unwrap();
+    let status = TypeBuilder::new_enum("TabStatus")
+        .variant("loading", None)
+        .variant("complete", Some("The tab has loaded."))
+        .build()
+        .unwrap();
+    let get = MethodBuilder::new("get")
+        .arg(
+            element("tabId", TypeRef::Primitive(Primitive::Integer)).unwrap(),
+            false,
+        )
+        .returns(TypeRef::Named("Tab".to_owned()), None)
+        .build()
+        .unwrap();
+    let on_created = EventBuilder::new("onCreated").build().unwrap();
+    let tabs = NamespaceBuilder::new("tabs")
+        .type_(tab)
+        .type_(status)
+        .method(get)
+        .event(on_created)
+        .permission("tabs")
+        .build()
+        .unwrap();
+
+    assert_eq!(tabs.types().len(), 2);
+    match tabs.types()[1].kind() {
+        TypeKind::Enum { variants } => {
+            assert_eq!(variants[1].description(), Some("The tab has loaded."))
+        }
+        _ => panic!("enum expected"),
+    }
+    assert_eq!(tabs.methods()[0].name(), "get");
+    assert_eq!(tabs.permissions(), ["tabs"]);
+}
+
+#[test]
+fn rejects_members_of_other_kinds() {
+    let error = TypeBuilder::new_struct("Tab")
+        .variant("loading", None)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error,
+        BuildError::Mismatch {
+            member: "enum variant",
+            kind: "struct",
+            name: "Tab".to_owned(),
+        }
+    );
+    assert_eq!(error.to_string(), "struct Tab cannot have enum variants");
+
+    let error = TypeBuilder::new_enum("TabStatus")
+        .element(element("id", string()).unwrap())
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        BuildError::Mismatch {
+            member: "element",
+            kind: "enum",
+            ..
+        }
+    ));
+
+    let method = MethodBuilder::new("get").build().unwrap();
+    let event = EventBuilder::new("onChanged").build().unwrap();
+    assert!(TypeBuilder::new_data("Blob")
+        .method(method.clone())
+        .build()
+        .is_err());
+    assert!(TypeBuilder::new_data("Blob").event(event).build().is_err());
+    assert!(TypeBuilder::new_enum("E")
+        .optional_element(element("id", string()).unwrap())
+        .build()
+        .is_err());
+
+    let error = PropertyBuilder::new_immediate("local", string())
+        .method(method.clone())
+        .build()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "property local cannot have methods");
+    assert!(PropertyBuilder::new_object("local")
+        .method(method)
+        .build()
+        .is_ok());
+}
+
+#[test]
+fn rejects_bad_names() {
+    assert_eq!(
+        TypeBuilder::new_data("").build().unwrap_err(),
+        BuildError::EmptyName { kind: "type" }
+    );
+    assert!(matches!(
+        element("1st", string()).unwrap_err(),
+        BuildError::InvalidName { .. }
+    ));
+    assert!(NamespaceBuilder::new("app.runtime").build().is_ok());
+    assert!(MethodBuilder::new("app.get").build().is_err());
+
+    let error = TypeBuilder::new_enum("TabStatus")
+        .variant("loading", None)
+        .variant("loading", None)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "duplicate enum variant loading in TabStatus"
+    );
+}
@@ -0,0 +1,47 @@
+// Helpers shared by the integration tests; each test crate uses only some of them.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use webext_parser::api::{ArgumentKind, Method, Namespace, Type, TypeRef};
+
+pub fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+// The contents of `tests/fixtures/{layout}/{name}.html`.
+pub fn html(layout: &str, name: &str) -> String {
+    let path = fixtures().join(layout).join(format!("{}.html", name));
+    std::fs::read_to_string(path).unwrap()
+}
+
+pub fn named(name: &str) -> TypeRef {
+    TypeRef::Named(name.to_owned())
+}
+
+pub fn namespace<'a>(namespaces: &'a [Namespace], name: &str) -> &'a Namespace {
+    namespaces.iter().find(|n| n.name() == name).unwrap()
+}
+
+pub fn type_named<'a>(namespace: &'a Namespace, name: &str) -> &'a Type {
+    namespace.types().iter().find(|t| t.name() == name).unwrap()
+}
+
+pub fn method<'a>(namespace: &'a Namespace, name: &str) -> &'a Method {
+    namespace
+        .methods()
+        .iter()
+        .find(|m| m.name() == name)
+        .unwrap()
+}
+
+// The type of the argument called `name`.
+pub fn arg<'a>(method: &'a Method, name: &str) -> &'a TypeRef {
+    method
+        .args()
+        .iter()
+        .find_map(|a| match a.kind() {
+            ArgumentKind::Element { element } if element.name() == name => Some(element.type_ref()),
+            _ => None,
+        })
+        .unwrap()
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use webext_parser::api::Namespace;
+use webext_parser::compat::{ItemKind, Matrix, Row};
+use webext_parser::schema::{self, SchemaOptions};
+
+fn load(name: &str) -> Vec<Namespace> {
+    let path = common::fixtures().join("compat").join(name);
+    let (namespaces, diagnostics) = schema::load_file(&path, &SchemaOptions::default()).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    namespaces
+}
+
+fn matrix() -> Matrix {
+    let chrome = load("chrome.json");
+    let firefox = load("firefox.json");
+    Matrix::new(&[("Chrome", &chrome), ("Firefox", &firefox)])
+}
+
+fn row<'a>(matrix: &'a Matrix, path: &str) -> &'a Row {
+    matrix.rows().iter().find(|r| r.path() == path).unwrap()
+}
+
+#[test]
+fn matches_items_across_browsers() {
+    let matrix = matrix();
+    assert_eq!(matrix.browsers(), ["Chrome", "Firefox"]);
+
+    let paths = matrix.rows().iter().map(|r| r.path()).collect::<Vec<_>>();
+    assert_eq!(
+        &paths[..4],
+        [
+            "tabGroups",
+            "tabGroups.get",
+            "tabGroups.get.groupId",
+            "tabs"
+        ]
+    );
+
+    let tab_groups = row(&matrix, "tabGroups");
+    assert_eq!(tab_groups.kind(), ItemKind::Namespace);
+    assert_eq!(tab_groups.signatures(), [Some(String::new()), None]);
+
+    let get = row(&matrix, "tabs.get");
+    assert_eq!(get.kind(), ItemKind::Method);
+    assert!(get.is_compatible());
+    assert_eq!(
+        get.signatures()[0].as_deref(),
+        Some("(tabId, callback) -> Promise<Tab>")
+    );
+    assert!(row(&matrix, "tabs.get.callback").is_compatible());
+    assert!(row(&matrix, "tabs.onActivated").is_compatible());
+    assert!(row(&matrix, "tabs.onActivated.activeInfo").is_compatible());
+
+    let unloaded = row(&matrix, "tabs.TabStatus.unloaded");
+    assert_eq!(unloaded.kind(), ItemKind::EnumValue);
+    assert_eq!(unloaded.signatures(), [Some(String::new()), None]);
+    assert!(row(&matrix, "tabs.TabStatus.loading").is_compatible());
+
+    let reader_mode = row(&matrix, "tabs.toggleReaderMode");
+    assert!(reader_mode.is_missing_anywhere());
+    assert!(!reader_mode.has_signature_differences());
+}
+
+#[test]
+fn signature_differences() {
+    let matrix = matrix();
+    let discard = row(&matrix, "tabs.discard");
+    assert!(!discard.is_missing_anywhere());
+    assert!(discard.has_signature_differences());
+    assert_eq!(
+        discard.signatures(),
+        [
+            Some("(tabId?, callback?) -> Promise<Tab>".to_owned()),
+            Some("(tabIds?) -> Promise<void>".to_owned())
+        ]
+    );
+    assert_eq!(
+        row(&matrix, "tabs.discard.tabIds").signatures()[1].as_deref(),
+        Some("optional integer or array of integer")
+    );
+    assert_eq!(
+        row(&matrix, "tabs.get.callback").signatures()[0].as_deref(),
+        Some("function(tab: Tab)")
+    );
+
+    let get_selected = row(&matrix, "tabs.getSelected");
+    assert_eq!(
+        get_selected.signatures()[0].as_deref(),
+        Some("(windowId?, callback)")
+    );
+
+    let incompatible = matrix
+        .incompatibilities()
+        .map(|r| r.path())
+        .collect::<Vec<_>>();
+    assert!(incompatible.contains(&"tabs.discard"));
+    assert!(!incompatible.contains(&"tabs.get"));
+}
+
+#[test]
+fn json_output() {
+    let json: serde_json::Value = serde_json::from_str(&matrix().to_json().unwrap()).unwrap();
+    assert_eq!(json["browsers"], serde_json::json!(["Chrome", "Firefox"]));
+    let discard = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["path"] == "tabs.discard")
+        .unwrap();
+    assert_eq!(discard["kind"], "method");
+    assert_eq!(discard["missing"], false);
+    assert_eq!(discard["differs"], true);
+    assert_eq!(
+        discard["signatures"]["Firefox"],
+        "(tabIds?) -> Promise<void>"
+    );
+    let reader_mode = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["path"] == "tabs.toggleReaderMode")
+        .unwrap();
+    assert!(reader_mode["signatures"]["Chrome"].is_null());
+}
+
+#[test]
+fn markdown_and_html_output() {
+    let matrix = matrix();
+    let markdown = matrix.to_markdown();
+    let lines = markdown.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "| Item | Kind | Chrome | Firefox |");
+    assert_eq!(lines[1], "| --- | --- | --- | --- |");
+    assert!(lines.contains(&"| `tabs.get` | method | yes | yes |"));
+    assert!(lines.contains(&"| `tabs.toggleReaderMode` | method | — | yes |"));
+    assert!(lines.contains(
+        &"| `tabs.discard` | method | `(tabId?, callback?) -> Promise<Tab>` | `(tabIds?) -> Promise<void>` |"
+    ));
+    assert_eq!(lines.len(), matrix.rows().len() + 2);
+
+    let html = matrix.to_html();
+    assert!(html.starts_with("<table class=\"compat\">"));
+    assert!(html.contains("<th>Chrome</th><th>Firefox</th>"));
+    assert!(html.contains(
+        "<tr class=\"differs\"><td><code>tabs.discard</code></td><td>method</td><td><code>(tabId?, callback?) -&gt; Promise&lt;Tab&gt;</code></td>"
+    ));
+    assert!(html.contains(
+        "<tr class=\"missing\"><td><code>tabs.toggleReaderMode</code></td><td>method</td><td class=\"absent\">—</td><td>yes</td></tr>"
+    ));
+    assert!(html.contains("<tr><td><code>tabs.get</code></td>"));
+}
@@ -0,0 +1,378 @@
+mod common;
+
+use common::{arg, method};
+use webext_parser::api::{ArgumentKind, Namespace, Primitive, PropertyKind, TypeKind, TypeRef};
+use webext_parser::error::Severity;
+use webext_parser::{Layout, ParseOptions};
+
+fn html(name: &str) -> String {
+    common::html("current", name)
+}
+
+fn fixture(name: &str) -> Namespace {
+    let (namespace, diagnostics) =
+        webext_parser::parse_api_html_with(name, &html(name), &ParseOptions::default()).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    namespace
+}
+
+#[test]
+fn detects_layout() {
+    assert_eq!(Layout::detect(&html("tabs")), Some(Layout::Current));
+    assert_eq!(Layout::detect(&html("input.ime")), Some(Layout::Current));
+    assert_eq!(Layout::detect(&html("index")), None);
+
+    let legacy = common::html("legacy", "tabs");
+    assert_eq!(Layout::detect(&legacy), Some(Layout::Legacy));
+}
+
+#[test]
+fn index() {
+    let pages = webext_parser::parse_index_html(Layout::Current, &html("index"));
+    let names = pages
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "accessibilityFeatures",
+            "devtools.inspectedWindow",
+            "input.ime",
+            "tabs"
+        ]
+    );
+    assert_eq!(
+        pages[3].1,
+        "https://developer.chrome.com/docs/extensions/reference/api/tabs"
+    );
+}
+
+#[test]
+fn types() {
+    let tabs = fixture("tabs");
+    assert_eq!(tabs.availability().since(), Some(5));
+    assert_eq!(tabs.permissions(), ["tabs"]);
+
+    let names = tabs.types().iter().map(|t| t.name()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "MutedInfoReason",
+            "MutedInfo",
+            "Tab",
+            "TabStatus",
+            "WindowType"
+        ]
+    );
+
+    for (name, values) in &[
+        ("MutedInfoReason", &["user", "capture", "extension"][..]),
+        ("TabStatus", &["unloaded", "loading", "complete"][..]),
+        (
+            "WindowType",
+            &["normal", "popup", "panel", "app", "devtools"][..],
+        ),
+    ] {
+        let t = tabs.types().iter().find(|t| t.name() == *name).unwrap();
+        match t.kind() {
+            TypeKind::Enum { variants } => assert_eq!(
+                variants.iter().map(|v| v.value()).collect::<Vec<_>>(),
+                *values
+            ),
+            _ => panic!("enum expected"),
+        }
+    }
+    let reason = &tabs.types()[0];
+    assert_eq!(reason.availability().since(), Some(46));
+    match reason.kind() {
+        TypeKind::Enum { variants } => assert_eq!(
+            variants[1].description(),
+            Some("Tab capture was started, forcing a muted state change.")
+        ),
+        _ => panic!("enum expected"),
+    }
+
+    match tabs.types()[2].kind() {
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            ..
+        } => {
+            let required = elements.iter().map(|e| e.name()).collect::<Vec<_>>();
+            assert_eq!(required, ["active", "highlighted", "index", "windowId"]);
+            let muted_info = optional_elements
+                .iter()
+                .find(|e| e.name() == "mutedInfo")
+                .unwrap();
+            assert_eq!(
+                muted_info.type_ref(),
+                &TypeRef::Named("MutedInfo".to_owned())
+            );
+            assert_eq!(muted_info.availability().since(), Some(46));
+        }
+        _ => panic!("struct expected"),
+    }
+}
+
+#[test]
+fn properties() {
+    let tabs = fixture("tabs");
+    let tab_id_none = &tabs.properties()[0];
+    assert_eq!(tab_id_none.name(), "TAB_ID_NONE");
+    assert_eq!(tab_id_none.availability().since(), Some(46));
+    match tab_id_none.kind() {
+        PropertyKind::Immediate { value, .. } => {
+            assert_eq!(value.as_ref().unwrap().to_string(), "-1")
+        }
+        _ => panic!("immediate property expected"),
+    }
+}
+
+#[test]
+fn methods() {
+    let tabs = fixture("tabs");
+    let query = method(&tabs, "query");
+    assert!(query.supports_promise());
+    assert!(query.returns().is_none());
+    match arg(query, "queryInfo") {
+        TypeRef::Object(Some(t)) => {
+            assert_eq!(t.name(), "QueryInfo");
+            match t.kind() {
+                TypeKind::Struct {
+                    elements,
+                    optional_elements,
+                    ..
+                } => {
+                    assert!(elements.is_empty());
+                    let url = optional_elements
+                        .iter()
+                        .find(|e| e.name() == "url")
+                        .unwrap();
+                    assert_eq!(url.type_ref().to_string(), "string or array of string");
+                }
+                _ => panic!("struct expected"),
+            }
+        }
+        t => panic!("unexpected type {}", t),
+    }
+    let result = arg(query.callback().unwrap(), "result");
+    assert_eq!(
+        result,
+        &TypeRef::Array(Box::new(TypeRef::Named("Tab".to_owned())))
+    );
+
+    let highlight = method(&tabs, "highlight");
+    assert_eq!(highlight.availability().since(), Some(16));
+    assert_eq!(
+        arg(highlight.callback().unwrap(), "window"),
+        &TypeRef::Named("windows.Window".to_owned())
+    );
+}
+
+#[test]
+fn promise_only_methods() {
+    let ime = fixture("input.ime");
+    let is_active = method(&ime, "isActive");
+    assert!(is_active.supports_promise());
+    assert!(is_active.returns().is_none());
+    assert_eq!(is_active.promise_args().len(), 1);
+    // The callback the promise stands for is made up from its type.
+    let callback = is_active.callback().unwrap();
+    assert_eq!(callback.name(), "callback");
+    assert!(is_active.args().last().unwrap().is_optional());
+    let params = is_active.promise_params().unwrap();
+    assert_eq!(params.len(), 1);
+    assert_eq!(
+        arg(callback, "result"),
+        &TypeRef::Primitive(Primitive::Boolean)
+    );
+    assert_eq!(
+        is_active.promise_type(),
+        Some(TypeRef::Primitive(Primitive::Boolean))
+    );
+}
+
+#[test]
+fn events() {
+    let tabs = fixture("tabs");
+    let on_created = &tabs.events()[0];
+    assert_eq!(on_created.name(), "onCreated");
+    let callback = on_created.callback();
+    assert!(on_created.extra_args().is_empty());
+    assert_eq!(arg(callback, "tab"), &TypeRef::Named("Tab".to_owned()));
+}
+
+#[test]
+fn nested_objects() {
+    let ime = fixture("input.ime");
+    assert_eq!(ime.availability().since(), Some(35));
+    assert_eq!(ime.availability().platforms(), ["chromeos"]);
+    assert_eq!(ime.permissions(), ["input"]);
+    assert_eq!(ime.manifest_keys(), ["input_components"]);
+
+    let parameters = match arg(method(&ime, "setCandidates"), "parameters") {
+        TypeRef::Object(Some(t)) => t,
+        t => panic!("unexpected type {}", t),
+    };
+    assert_eq!(parameters.name(), "Parameters");
+    let candidates = match parameters.kind() {
+        TypeKind::Struct { elements, .. } => elements.iter().find(|e| e.name() == "candidates"),
+        _ => None,
+    }
+    .unwrap();
+    match candidates.type_ref() {
+        TypeRef::Array(item) => match &**item {
+            TypeRef::Object(Some(t)) => {
+                assert_eq!(t.name(), "ParametersCandidates");
+                match t.kind() {
+                    TypeKind::Struct {
+                        elements,
+                        optional_elements,
+                        ..
+                    } => {
+                        assert_eq!(elements.len(), 2);
+                        assert_eq!(optional_elements.len(), 3);
+                        assert_eq!(
+                            optional_elements[2].type_ref().rustify(),
+                            "ParametersCandidatesUsage"
+                        );
+                    }
+                    _ => panic!("struct expected"),
+                }
+            }
+            t => panic!("unexpected item type {}", t),
+        },
+        t => panic!("unexpected type {}", t),
+    }
+}
+
+#[test]
+fn inline_argument_names() {
+    let method = |name: &str, arg: &str, field: &str| {
+        format!(
+            "<h3 id=\"method-{0}\">{0}()</h3><h4>Parameters</h4><ul>\
+             <li><p><code>{1}</code></p><p>object</p>\
+             <ul><li><p><code>{2}</code></p><p>string</p></li></ul></li></ul>",
+            name, arg, field
+        )
+    };
+    let html = format!(
+        "<article class=\"devsite-article\"><h1 class=\"devsite-page-title\">chrome.test</h1>\
+         <div class=\"devsite-article-body\"><h2 id=\"method\">Methods</h2>{}{}{}</div></article>",
+        method("create", "options", "url"),
+        method("update", "options", "title"),
+        method("query", "queryInfo", "url")
+    );
+    let (test, _) =
+        webext_parser::parse_api_html_with("test", &html, &ParseOptions::default()).unwrap();
+    let names = test
+        .methods()
+        .iter()
+        .map(|m| match m.args()[0].kind() {
+            ArgumentKind::Element { element } => match element.type_ref() {
+                TypeRef::Object(Some(t)) => t.name().to_owned(),
+                t => panic!("unexpected type {}", t),
+            },
+            _ => panic!("element expected"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["CreateOptions", "UpdateOptions", "QueryInfo"]);
+}
+
+#[test]
+fn skips_broken_methods() {
+    let (alarms, diagnostics) =
+        webext_parser::parse_api_html_with("alarms", &html("alarms"), &ParseOptions::default())
+            .unwrap();
+    let names = alarms
+        .methods()
+        .iter()
+        .map(|m| m.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["clear", "clearAll"]);
+    assert_eq!(alarms.permissions(), ["alarms"]);
+    assert!(alarms.description().is_some());
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity(), Severity::Error);
+    assert_eq!(diagnostics[0].error().item(), Some("create"));
+
+    let strict = ParseOptions::default().with_strict(true);
+    assert!(webext_parser::parse_api_html_with("alarms", &html("alarms"), &strict).is_err());
+}
+
+#[test]
+fn error_locations() {
+    let strict = ParseOptions::default().with_strict(true);
+    let error = webext_parser::parse_api_html_with("alarms", &html("alarms"), &strict).unwrap_err();
+    assert_eq!(error.namespace(), Some("alarms"));
+    assert_eq!(error.section(), Some("method"));
+    assert_eq!(error.item(), Some("create"));
+    assert_eq!(
+        error.node(),
+        Some("main > article.devsite-article > div.devsite-article-body > ul > li")
+    );
+
+    // A page forced to this layout without any of its sections.
+    let options = ParseOptions::default().with_layout(Layout::Current);
+    let error = webext_parser::parse_api_html_with("alarms", "<p></p>", &options).unwrap_err();
+    assert_eq!(error.to_string(), "alarms: no API reference found");
+}
+
+#[test]
+fn explicit_layout() {
+    let options = ParseOptions::default().with_layout(Layout::Legacy);
+    let (tabs, _) = webext_parser::parse_api_html_with("tabs", &html("tabs"), &options).unwrap();
+    assert!(tabs.methods().is_empty());
+
+    let options = ParseOptions::default().with_layout(Layout::Current);
+    let (tabs, _) = webext_parser::parse_api_html_with("tabs", &html("tabs"), &options).unwrap();
+    assert_eq!(tabs.methods().len(), 3);
+}
+
+#[test]
+fn permissions() {
+    let storage = fixture("storage");
+    // Each permission once, however often the page names it.
+    assert_eq!(storage.permissions(), ["storage"]);
+    assert_eq!(storage.optional_permissions(), ["unlimitedStorage"]);
+    assert!(fixture("tabs").optional_permissions().is_empty());
+}
+
+#[test]
+fn type_members() {
+    let storage = fixture("storage");
+    let area = &storage.types()[0];
+    let (methods, events) = match area.kind() {
+        TypeKind::Struct {
+            optional_elements,
+            methods,
+            events,
+            ..
+        } => {
+            assert_eq!(optional_elements[0].name(), "QUOTA_BYTES");
+            (methods, events)
+        }
+        _ => panic!("struct expected"),
+    };
+
+    let names = methods.iter().map(|m| m.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["get", "setAccessLevel"]);
+    assert!(methods[0].supports_promise());
+    assert_eq!(
+        methods[0].description(),
+        Some("Gets one or more items from storage.")
+    );
+    assert_eq!(methods[0].args().len(), 2);
+    assert_eq!(methods[1].availability().since(), Some(102));
+    match arg(&methods[1], "accessOptions") {
+        TypeRef::Object(Some(t)) => assert_eq!(t.name(), "AccessOptions"),
+        t => panic!("unexpected type {}", t),
+    }
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name(), "onChanged");
+    assert_eq!(events[0].availability().since(), Some(73));
+    assert_eq!(events[0].callback().args().len(), 1);
+}
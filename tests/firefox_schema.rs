@@ -0,0 +1,212 @@
+mod common;
+
+use common::{namespace, type_named};
+use webext_parser::api::{ArgumentKind, Namespace, Primitive, PropertyKind, TypeKind, TypeRef};
+use webext_parser::schema::{self, SchemaOptions};
+
+fn load() -> Vec<Namespace> {
+    let root = common::fixtures().join("schema/firefox");
+    let toolkit = root.join("toolkit");
+    let browser = root.join("browser");
+    let (namespaces, diagnostics) =
+        schema::load_dirs(&[&toolkit, &browser], &SchemaOptions::default()).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    namespaces
+}
+
+#[test]
+fn merges_namespaces_across_files() {
+    let namespaces = load();
+    let names = namespaces.iter().map(|n| n.name()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["extensionTypes", "manifest", "storage", "browserAction"]
+    );
+
+    let storage = namespace(&namespaces, "storage");
+    assert_eq!(
+        storage.description(),
+        Some("Use the `browser.storage` API to store data.")
+    );
+    assert_eq!(storage.permissions(), ["storage"]);
+    assert_eq!(storage.manifest_keys(), ["storage"]);
+    // Taken from the `OptionalPermission` types the file extends.
+    assert_eq!(storage.optional_permissions(), ["unlimitedStorage"]);
+    assert!(namespace(&namespaces, "manifest")
+        .optional_permissions()
+        .is_empty());
+    let properties = storage
+        .properties()
+        .iter()
+        .map(|p| p.name())
+        .collect::<Vec<_>>();
+    assert_eq!(properties, ["local", "session"]);
+}
+
+#[test]
+fn applies_extensions() {
+    let namespaces = load();
+    let manifest = namespace(&namespaces, "manifest");
+
+    match type_named(manifest, "PermissionNoPrompt").kind() {
+        TypeKind::Enum { variants } => {
+            let values = variants.iter().map(|v| v.value()).collect::<Vec<_>>();
+            assert_eq!(values, ["idle", "alarms", "storage"]);
+        }
+        _ => panic!("enum expected"),
+    }
+
+    match type_named(manifest, "WebExtensionManifest").kind() {
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            ..
+        } => {
+            assert_eq!(elements.len(), 2);
+            let optional = optional_elements
+                .iter()
+                .map(|e| e.name())
+                .collect::<Vec<_>>();
+            assert_eq!(optional, ["permissions", "browser_action"]);
+            let browser_action = &optional_elements[1];
+            assert_eq!(
+                browser_action.availability().max_manifest_version(),
+                Some(2)
+            );
+            match browser_action.type_ref() {
+                TypeRef::Object(Some(t)) => {
+                    assert_eq!(t.name(), "WebExtensionManifestBrowserAction")
+                }
+                _ => panic!("inline object expected"),
+            }
+        }
+        _ => panic!("struct expected"),
+    }
+    assert!(matches!(
+        type_named(manifest, "PermissionOrOrigin").kind(),
+        TypeKind::Data
+    ));
+}
+
+#[test]
+fn skips_unsupported_items() {
+    let namespaces = load();
+    let storage = namespace(&namespaces, "storage");
+    assert!(storage.properties().iter().all(|p| p.name() != "managed"));
+    match type_named(storage, "StorageArea").kind() {
+        TypeKind::Struct { methods, .. } => {
+            let names = methods.iter().map(|m| m.name()).collect::<Vec<_>>();
+            assert_eq!(names, ["get"]);
+        }
+        _ => panic!("struct expected"),
+    }
+    match type_named(namespace(&namespaces, "manifest"), "WebExtensionManifest").kind() {
+        TypeKind::Struct {
+            optional_elements, ..
+        } => assert!(optional_elements.iter().all(|e| e.name() != "applications")),
+        _ => panic!("struct expected"),
+    }
+}
+
+#[test]
+fn contexts_and_manifest_versions() {
+    let namespaces = load();
+    let storage = namespace(&namespaces, "storage");
+    assert_eq!(storage.availability().contexts(), ["content", "devtools"]);
+    let session = storage
+        .properties()
+        .iter()
+        .find(|p| p.name() == "session")
+        .unwrap();
+    assert_eq!(session.availability().min_manifest_version(), Some(3));
+    assert!(!session.availability().is_available_in_manifest(2));
+    assert!(session.availability().is_available_in_manifest(3));
+    match session.kind() {
+        PropertyKind::Immediate { type_ref, value } => {
+            assert_eq!(type_ref, &TypeRef::Named("StorageArea".to_owned()));
+            assert!(value.is_none());
+        }
+        _ => panic!("immediate property expected"),
+    }
+
+    let browser_action = namespace(&namespaces, "browserAction");
+    assert_eq!(
+        browser_action.availability().max_manifest_version(),
+        Some(2)
+    );
+    assert!(browser_action.permissions().is_empty());
+    assert_eq!(browser_action.manifest_keys(), ["browser_action"]);
+}
+
+#[test]
+fn async_functions_and_null_choices() {
+    let namespaces = load();
+    let browser_action = namespace(&namespaces, "browserAction");
+    let set_title = &browser_action.methods()[0];
+    assert!(set_title.supports_promise());
+    assert_eq!(set_title.args().len(), 1);
+
+    match type_named(namespace(&namespaces, "storage"), "StorageArea").kind() {
+        TypeKind::Struct { methods, .. } => {
+            let get = &methods[0];
+            assert!(get.supports_promise());
+            assert_eq!(get.args().len(), 2);
+        }
+        _ => panic!("struct expected"),
+    }
+
+    let details = match set_title.args()[0].kind() {
+        ArgumentKind::Element { element } => element.type_ref(),
+        _ => panic!("element expected"),
+    };
+    match details {
+        TypeRef::Object(Some(details)) => match details.kind() {
+            TypeKind::Struct { elements, .. } => {
+                let title = elements.iter().find(|e| e.name() == "title").unwrap();
+                assert_eq!(title.type_ref(), &TypeRef::Primitive(Primitive::String));
+            }
+            _ => panic!("struct expected"),
+        },
+        _ => panic!("inline object expected"),
+    }
+}
+
+#[test]
+fn reports_unapplied_entries() {
+    let dir = common::fixtures().join("schema/extend");
+    let (namespaces, diagnostics) = schema::load_dir(&dir, &SchemaOptions::default()).unwrap();
+    let messages = diagnostics
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "fourth: no namespace missing to import",
+            "third: ambiguous type Shared to extend, candidates are first.Shared, second.Shared",
+            "third: no type Missing to extend",
+            "third: no type second.Color to extend",
+            "third: cannot extend enum first.Color with a struct",
+        ]
+    );
+
+    // The unique and qualified targets are still extended.
+    match type_named(namespace(&namespaces, "second"), "Only").kind() {
+        TypeKind::Struct { elements, .. } => assert_eq!(elements.len(), 2),
+        _ => panic!("struct expected"),
+    }
+    match type_named(namespace(&namespaces, "first"), "Color").kind() {
+        TypeKind::Enum { variants } => assert_eq!(variants.len(), 2),
+        _ => panic!("enum expected"),
+    }
+    for shared in ["first", "second"] {
+        match type_named(namespace(&namespaces, shared), "Shared").kind() {
+            TypeKind::Struct { elements, .. } => assert_eq!(elements.len(), 1),
+            _ => panic!("struct expected"),
+        }
+    }
+
+    let strict = SchemaOptions::default().with_strict(true);
+    let error = schema::load_dir(&dir, &strict).unwrap_err();
+    assert_eq!(error.to_string(), "fourth: no namespace missing to import");
+}
@@ -0,0 +1,457 @@
+mod common;
+
+use common::{method, type_named};
+use webext_parser::api::{
+    ArgumentKind, Channel, Element, Method, Namespace, Primitive, Type, TypeKind, TypeRef,
+};
+use webext_parser::error::Severity;
+use webext_parser::ParseOptions;
+
+fn html(name: &str) -> String {
+    common::html("legacy", name)
+}
+
+fn fixture(name: &str) -> Namespace {
+    webext_parser::parse_api_html(name, &html(name)).unwrap()
+}
+
+fn arg<'a>(method: &'a Method, name: &str) -> &'a Element {
+    method
+        .args()
+        .iter()
+        .find_map(|a| match a.kind() {
+            ArgumentKind::Element { element } if element.name() == name => Some(element),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn member<'a>(t: &'a Type, name: &str) -> &'a Element {
+    match t.kind() {
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            ..
+        } => elements
+            .iter()
+            .chain(optional_elements)
+            .find(|e| e.name() == name)
+            .unwrap(),
+        _ => panic!("struct expected"),
+    }
+}
+
+// A page with one struct type, `Test`, whose rows are given as (type cell, name) pairs.
+fn properties(rows: &[(&str, &str)]) -> Namespace {
+    let rows = rows
+        .iter()
+        .map(|(type_cell, name)| {
+            format!(
+                "<tr id=\"property-Test-{1}\"><td>{0}</td><td>{1}</td><td class=\"description\"></td></tr>",
+                type_cell, name
+            )
+        })
+        .collect::<String>();
+    let html = format!(
+        "<div class=\"api-reference\"><h2 id=\"types\">Types</h2><div><h3 id=\"type-Test\">Test</h3>\
+         <table><tr><th colspan=\"3\">properties</th></tr>{}</table></div></div>",
+        rows
+    );
+    webext_parser::parse_api_html("test", &html).unwrap()
+}
+
+// A page with nothing but its intro table, e.g. `intro(&[("Availability", "Since Chrome 5.")])`.
+fn intro(rows: &[(&str, &str)]) -> Namespace {
+    let rows = rows
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "<tr><td><strong>{}:</strong></td><td>{}</td></tr>",
+                key, value
+            )
+        })
+        .collect::<String>();
+    let html = format!(
+        "<table class=\"intro\">{}</table><div class=\"api-reference\"></div>",
+        rows
+    );
+    webext_parser::parse_api_html("test", &html).unwrap()
+}
+
+#[test]
+fn platform_notes() {
+    let ime = fixture("input.ime");
+    assert_eq!(ime.availability().since(), Some(35));
+    assert_eq!(ime.availability().platforms(), ["chromeos"]);
+
+    let both = intro(&[("Availability", "Since Chrome 40. Windows and Mac only.")]);
+    assert_eq!(both.availability().platforms(), ["win", "mac"]);
+    let after = intro(&[("Availability", "Available only on Chrome OS or Linux.")]);
+    assert_eq!(after.availability().platforms(), ["chromeos", "linux"]);
+}
+
+#[test]
+fn platform_false_positives() {
+    let tabs = fixture("tabs");
+    assert!(tabs.availability().platforms().is_empty());
+
+    let page = intro(&[(
+        "Description",
+        "Only windows of type normal are returned. Gets the machine only. Works on Android \
+         only if the tab is visible.",
+    )]);
+    assert!(page.availability().platforms().is_empty());
+}
+
+#[test]
+fn channels() {
+    let dev = intro(&[("Availability", "Dev channel only.")]);
+    assert_eq!(dev.availability().channel(), Channel::Dev);
+    let beta = intro(&[("Availability", "Beta and Dev channels only.")]);
+    assert_eq!(beta.availability().channel(), Channel::Beta);
+
+    let page = intro(&[(
+        "Description",
+        "Reports the channel of the browser, including beta builds. Use the \"update channel\" \
+         setting to switch.",
+    )]);
+    assert_eq!(page.availability().channel(), Channel::Stable);
+    assert_eq!(fixture("tabs").availability().channel(), Channel::Stable);
+}
+
+#[test]
+fn inline_argument_names() {
+    let method = |name: &str, field: &str| {
+        format!(
+            "<div><h3 id=\"method-{0}\">{0}</h3><div class=\"description\"><table><tbody>\
+             <tr id=\"property-{0}-options\"><td>object</td><td>options</td>\
+             <td class=\"description\"><table><tbody>\
+             <tr id=\"property-options-{1}\"><td>string</td><td>{1}</td><td class=\"description\"></td></tr>\
+             </tbody></table></td></tr></tbody></table></div></div>",
+            name, field
+        )
+    };
+    let html = format!(
+        "<div class=\"api-reference\"><h2 id=\"methods\">Methods</h2>{}{}</div>",
+        method("create", "url"),
+        method("update", "title")
+    );
+    let namespace = webext_parser::parse_api_html("test", &html).unwrap();
+    let names = namespace
+        .methods()
+        .iter()
+        .map(|m| match m.args()[0].kind() {
+            ArgumentKind::Element { element } => match element.type_ref() {
+                TypeRef::Object(Some(t)) => t.name().to_owned(),
+                t => panic!("unexpected type {}", t),
+            },
+            _ => panic!("element expected"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["CreateOptions", "UpdateOptions"]);
+}
+
+#[test]
+fn permissions_by_sentence() {
+    let tabs = fixture("tabs");
+    assert_eq!(tabs.permissions(), ["tabs"]);
+    assert!(tabs.optional_permissions().is_empty());
+
+    let page = intro(&[(
+        "Permissions",
+        "<code>\"storage\"</code> is required. The <code>\"storage\"</code> area can also \
+         be cleared. You can request <code>\"unlimitedStorage\"</code> as an optional \
+         permission.",
+    )]);
+    assert_eq!(page.permissions(), ["storage"]);
+    assert_eq!(page.optional_permissions(), ["unlimitedStorage"]);
+}
+
+#[test]
+fn promise_support() {
+    let method = |name: &str, summary: &str, description: &str| {
+        format!(
+            "<div><h3 id=\"method-{0}\">{0}</h3><div class=\"summary\"><code class=\"prettyprint\">\
+             {1}</code></div><div class=\"description\"><p>{2}</p></div></div>",
+            name, summary, description
+        )
+    };
+    let html = format!(
+        "<div class=\"api-reference\"><h2 id=\"methods\">Methods</h2>{}{}{}{}</div>",
+        method(
+            "get",
+            "Promise&lt;Tab&gt; chrome.tabs.get(integer tabId)",
+            "Retrieves details about the specified tab."
+        ),
+        method(
+            "resolve",
+            "chrome.test.resolve(PromiseInfo info)",
+            "Settles a PromiseInfo. Promise rejections are logged."
+        ),
+        method(
+            "clear",
+            "chrome.test.clear()",
+            "Clears everything. This method returns a Promise if no callback is given."
+        ),
+        method(
+            "getURL",
+            "string chrome.test.getURL(string path)",
+            "Converts a relative path to a URL."
+        ),
+    );
+    let namespace = webext_parser::parse_api_html("test", &html).unwrap();
+    let supported = namespace
+        .methods()
+        .iter()
+        .map(|m| (m.name(), m.supports_promise()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        supported,
+        [
+            ("get", true),
+            ("resolve", false),
+            ("clear", true),
+            ("getURL", false)
+        ]
+    );
+    assert!(namespace.methods()[0].returns().is_none());
+    assert_eq!(
+        namespace.methods()[3]
+            .returns()
+            .unwrap()
+            .type_ref()
+            .to_string(),
+        "string"
+    );
+
+    let tabs = fixture("tabs");
+    assert!(tabs.methods().iter().all(|m| m.promise_type().is_none()));
+}
+
+#[test]
+fn descriptions() {
+    let tabs = fixture("tabs");
+    assert_eq!(
+        tabs.description(),
+        Some(
+            "Use the `chrome.tabs` API to interact with the browser's tab system. You can use \
+             this API to create, modify, and rearrange tabs in the browser."
+        )
+    );
+    let muted_info = type_named(&tabs, "MutedInfo");
+    assert_eq!(
+        muted_info.description(),
+        Some("The tab's muted state and the reason for the last state change.")
+    );
+
+    let tab = type_named(&tabs, "Tab");
+    assert_eq!(
+        member(tab, "status").description(),
+        Some("Either *loading* or *complete*.")
+    );
+    let id = member(tab, "id").description().unwrap();
+    assert!(id.contains("foreign tabs using the [sessions](sessions) API"));
+    assert!(id.ends_with("set to `chrome.tabs.TAB_ID_NONE` for apps and devtools windows."));
+    assert_eq!(
+        member(tab, "mutedInfo").description(),
+        Some("The tab's muted state and the reason for the last state change.\n\n**Since Chrome 46.**")
+    );
+
+    // Nested parameter tables stay out of the text they sit in.
+    let get = method(&tabs, "get");
+    assert_eq!(
+        get.description(),
+        Some("Retrieves details about the specified tab.")
+    );
+    assert_eq!(arg(get, "tabId").description(), None);
+    assert_eq!(
+        get.callback().unwrap().description(),
+        Some(
+            "The *callback* parameter should be a function that looks like this:\n\n\
+             ```\nfunction(Tab tab) {...};\n```"
+        )
+    );
+    assert_eq!(
+        tabs.events()[0].callback().args()[0].description(),
+        Some("Details of the tab that was created.")
+    );
+
+    let page = intro(&[(
+        "Description",
+        "<p>Runs <b>one</b> of:</p><ul><li>the <code>run</code>\n  method,</li>\
+         <li><span class=\"optional\">optional</span> a <a href=\"#type-Task\">task</a>.</li></ul>\
+         <pre>\nchrome.test.run();\n</pre><table><tr><td>Skipped</td></tr></table>",
+    )]);
+    assert_eq!(
+        page.description(),
+        Some("Runs **one** of:\n\n- the `run` method,\n\n- a [task](#type-Task).\n\n```\nchrome.test.run();\n```")
+    );
+}
+
+#[test]
+fn type_refs() {
+    let tabs = fixture("tabs");
+    let query = method(&tabs, "query");
+    let query_info = match arg(query, "queryInfo").type_ref() {
+        TypeRef::Object(Some(t)) => t,
+        t => panic!("unexpected type {}", t),
+    };
+    assert_eq!(query_info.name(), "QueryInfo");
+    assert_eq!(
+        member(query_info, "windowId").type_ref(),
+        &TypeRef::Primitive(Primitive::Integer)
+    );
+    assert_eq!(
+        member(query_info, "windowType").type_ref(),
+        &TypeRef::Named("WindowType".to_owned())
+    );
+    assert_eq!(
+        arg(method(&tabs, "get"), "tabId").type_ref(),
+        &TypeRef::Primitive(Primitive::Integer)
+    );
+
+    let page = properties(&[
+        ("any", "value"),
+        ("ArrayBuffer", "data"),
+        ("function", "handler"),
+        ("array of array of <a href=\"#type-Tab\">Tab</a>", "groups"),
+        (
+            "enum of <code>\"first\"</code>, or <code>\"last\"</code>",
+            "position",
+        ),
+    ]);
+    let test = type_named(&page, "Test");
+    let types = ["value", "data", "handler", "groups", "position"]
+        .iter()
+        .map(|name| member(test, name).type_ref().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        [
+            TypeRef::Any,
+            TypeRef::Binary,
+            TypeRef::Function,
+            TypeRef::Array(Box::new(TypeRef::Array(Box::new(TypeRef::Named(
+                "Tab".to_owned()
+            ))))),
+            TypeRef::Enum(vec!["first".to_owned(), "last".to_owned()]),
+        ]
+    );
+}
+
+#[test]
+fn enum_variants() {
+    let tabs = fixture("tabs");
+    let variants = |name: &str| match type_named(&tabs, name).kind() {
+        TypeKind::Enum { variants } => variants
+            .iter()
+            .map(|v| (v.value(), v.description()))
+            .collect::<Vec<_>>(),
+        _ => panic!("enum expected"),
+    };
+    assert_eq!(
+        variants("MutedInfoReason"),
+        [
+            ("user", Some("A user input action set the muted state.")),
+            (
+                "capture",
+                Some("Tab capture was started, forcing a muted state change.")
+            ),
+            (
+                "extension",
+                Some("An extension, identified by the extensionId field, set the muted state.")
+            ),
+        ]
+    );
+    assert_eq!(
+        variants("WindowType"),
+        [
+            ("normal", None),
+            ("popup", None),
+            ("panel", None),
+            ("app", None),
+            ("devtools", None)
+        ]
+    );
+}
+
+#[test]
+fn unions() {
+    let tabs = fixture("tabs");
+    let highlight_info = match arg(method(&tabs, "highlight"), "highlightInfo").type_ref() {
+        TypeRef::Object(Some(t)) => t,
+        t => panic!("unexpected type {}", t),
+    };
+    let integer = TypeRef::Primitive(Primitive::Integer);
+    assert_eq!(
+        member(highlight_info, "tabs").type_ref(),
+        &TypeRef::Union(vec![
+            integer.clone(),
+            TypeRef::Array(Box::new(integer.clone()))
+        ])
+    );
+
+    let tab = TypeRef::Named("Tab".to_owned());
+    let page = properties(&[
+        (
+            "<a href=\"#type-Tab\">Tab</a> or array of <a href=\"#type-Tab\">Tab</a>",
+            "tabs",
+        ),
+        ("integer or array of (integer or string)", "ids"),
+        ("string or integer or string", "key"),
+    ]);
+    let test = type_named(&page, "Test");
+    assert_eq!(
+        member(test, "tabs").type_ref(),
+        &TypeRef::Union(vec![tab.clone(), TypeRef::Array(Box::new(tab))])
+    );
+    assert_eq!(
+        member(test, "ids").type_ref(),
+        &TypeRef::Union(vec![
+            integer.clone(),
+            TypeRef::Array(Box::new(TypeRef::Union(vec![
+                integer.clone(),
+                TypeRef::Primitive(Primitive::String)
+            ])))
+        ])
+    );
+    assert_eq!(
+        member(test, "key").type_ref(),
+        &TypeRef::Union(vec![TypeRef::Primitive(Primitive::String), integer])
+    );
+}
+
+#[test]
+fn skips_broken_methods() {
+    let (alarms, diagnostics) =
+        webext_parser::parse_api_html_with("alarms", &html("alarms"), &ParseOptions::default())
+            .unwrap();
+    let names = alarms
+        .methods()
+        .iter()
+        .map(|m| m.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["clear", "clearAll"]);
+    assert_eq!(alarms.permissions(), ["alarms"]);
+    assert_eq!(alarms.availability().since(), Some(22));
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity(), Severity::Error);
+    assert_eq!(diagnostics[0].error().item(), Some("create"));
+
+    let strict = ParseOptions::default().with_strict(true);
+    assert!(webext_parser::parse_api_html_with("alarms", &html("alarms"), &strict).is_err());
+}
+
+#[test]
+fn error_locations() {
+    let strict = ParseOptions::default().with_strict(true);
+    let error = webext_parser::parse_api_html_with("alarms", &html("alarms"), &strict).unwrap_err();
+    assert_eq!(error.namespace(), Some("alarms"));
+    assert_eq!(error.section(), Some("methods"));
+    assert_eq!(error.item(), Some("create"));
+    assert_eq!(
+        error.node(),
+        Some("div#gc-pagecontent > div.api-reference > div > div.description > table > tbody > tr#property-create-alarmInfo")
+    );
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::named;
+use webext_parser::api::{ArgumentKind, Namespace, TypeRef};
+use webext_parser::builder::{element, MethodBuilder, NamespaceBuilder, TypeBuilder};
+use webext_parser::link::{self, DiagnosticKind};
+
+// A namespace with the given types and a `get` method taking one argument per reference.
+fn namespace(name: &str, types: &[&str], references: &[&str]) -> Namespace {
+    let mut get = MethodBuilder::new("get");
+    for (i, reference) in references.iter().enumerate() {
+        get = get.arg(
+            element(&format!("arg{}", i), named(reference)).unwrap(),
+            false,
+        );
+    }
+    let mut builder = NamespaceBuilder::new(name).method(get.build().unwrap());
+    for t in types {
+        builder = builder.type_(TypeBuilder::new_data(t).build().unwrap());
+    }
+    builder.build().unwrap()
+}
+
+fn references(namespace: &Namespace) -> Vec<&TypeRef> {
+    namespace.methods()[0]
+        .args()
+        .iter()
+        .map(|a| match a.kind() {
+            ArgumentKind::Element { element } => element.type_ref(),
+            _ => panic!("element expected"),
+        })
+        .collect()
+}
+
+#[test]
+fn prefers_local_types() {
+    let mut namespaces = vec![
+        namespace("tabs", &["Tab"], &[]),
+        namespace("sessions", &["Tab"], &["Tab"]),
+    ];
+    assert!(link::link(&mut namespaces).is_empty());
+    assert_eq!(references(&namespaces[1]), [&named("sessions.Tab")]);
+}
+
+#[test]
+fn resolves_unique_global_types() {
+    let mut namespaces = vec![
+        namespace("windows", &["Window"], &[]),
+        namespace("tabs", &["Tab"], &["Window", "windows.Window", "Tab"]),
+    ];
+    assert!(link::link(&mut namespaces).is_empty());
+    assert_eq!(
+        references(&namespaces[1]),
+        [
+            &named("windows.Window"),
+            &named("windows.Window"),
+            &named("tabs.Tab")
+        ]
+    );
+}
+
+#[test]
+fn resolves_sibling_namespaces() {
+    let mut namespaces = vec![
+        namespace("devtools.inspectedWindow", &["Resource"], &[]),
+        namespace("devtools.panels", &[], &["inspectedWindow.Resource"]),
+    ];
+    assert!(link::link(&mut namespaces).is_empty());
+    assert_eq!(
+        references(&namespaces[1]),
+        [&named("devtools.inspectedWindow.Resource")]
+    );
+}
+
+#[test]
+fn reports_ambiguous_references() {
+    let mut namespaces = vec![
+        namespace("tabs", &["Tab"], &[]),
+        namespace("sessions", &["Tab"], &[]),
+        namespace("history", &[], &["Tab"]),
+    ];
+    let diagnostics = link::link(&mut namespaces);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.location(), "history.get.arg0");
+    assert_eq!(diagnostic.reference(), "Tab");
+    assert_eq!(
+        diagnostic.kind(),
+        &DiagnosticKind::Ambiguous {
+            candidates: vec!["tabs.Tab".to_owned(), "sessions.Tab".to_owned()]
+        }
+    );
+    assert_eq!(
+        diagnostic.to_string(),
+        "history.get.arg0: ambiguous reference Tab (candidates: tabs.Tab, sessions.Tab)"
+    );
+    // Unresolved references are left as they are.
+    assert_eq!(references(&namespaces[2]), [&named("Tab")]);
+}
+
+#[test]
+fn reports_dangling_references() {
+    let mut namespaces = vec![namespace(
+        "tabs",
+        &["Tab"],
+        &["Missing", "windows.Window", "tabs.Missing"],
+    )];
+    let diagnostics = link::link(&mut namespaces);
+    let dangling = diagnostics
+        .iter()
+        .map(|d| {
+            assert_eq!(d.kind(), &DiagnosticKind::Dangling);
+            d.reference()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(dangling, ["Missing", "windows.Window", "tabs.Missing"]);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "tabs.get.arg0: reference to undefined type Missing"
+    );
+}
+
+#[test]
+fn links_with_shared_namespaces() {
+    let shared = vec![
+        namespace("extensionTypes", &["Date"], &[]),
+        namespace("tabs", &["Tab"], &[]),
+    ];
+    // The `tabs` here hides the shared one, so its `Tab` is the only candidate.
+    let mut namespaces = vec![
+        namespace("tabs", &["Tab"], &[]),
+        namespace("messages", &[], &["Date", "Tab"]),
+    ];
+    assert!(link::link_with(&mut namespaces, &shared).is_empty());
+    assert_eq!(
+        references(&namespaces[1]),
+        [&named("extensionTypes.Date"), &named("tabs.Tab")]
+    );
+
+    let mut namespaces = vec![namespace("messages", &[], &["Date"])];
+    let diagnostics = link::link(&mut namespaces);
+    assert_eq!(diagnostics[0].kind(), &DiagnosticKind::Dangling);
+}
@@ -0,0 +1,191 @@
+use std::panic;
+use webext_parser::api::TypeRef;
+use webext_parser::ParseOptions;
+
+// A small linear congruential generator, so runs are reproducible without extra crates.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+const FRAGMENTS: &[&str] = &[
+    r#"<div class="api-reference">"#,
+    "</div>",
+    "<div>",
+    r#"<h2 id="types">Types</h2>"#,
+    r#"<h2 id="properties">Properties</h2>"#,
+    r#"<h2 id="methods">Methods</h2>"#,
+    r#"<h2 id="events">Events</h2>"#,
+    r#"<h2 id="unknown">Unknown</h2>"#,
+    "<h2>",
+    r#"<h2 id="type">Types</h2>"#,
+    r#"<h2 id="method">Methods</h2>"#,
+    r#"<h3 id="type-Foo">Foo</h3>"#,
+    r#"<h3 id="method-bar">bar</h3>"#,
+    r#"<h3 id="event-onBaz">onBaz</h3>"#,
+    "<h3></h3>",
+    "<h4>addListener</h4>",
+    "<h4>Properties</h4>",
+    "<h4>Parameters</h4>",
+    "<h4>Returns</h4>",
+    "<ul>",
+    "</ul>",
+    "<li>",
+    "</li>",
+    "<p>string | string[] optional</p>",
+    r#"<p><span class="badge">Chrome 46+</span></p>"#,
+    r#"<div class="description">"#,
+    r#"<div class="summary"><code class="prettyprint">chrome.foo.bar(function callback)</code></div>"#,
+    r#"<div class="summary"><code class="prettyprint">"#,
+    "<table>",
+    "</table>",
+    "<tbody>",
+    "<tr>",
+    r#"<tr id="property-x">"#,
+    "</tr>",
+    "<th>",
+    r#"<th colspan="3">properties</th>"#,
+    r#"<th colspan="2">Enum</th>"#,
+    "<th>methods</th>",
+    "<th>events</th>",
+    "<th>Returns</th>",
+    "<th>Parameters</th>",
+    "<td>",
+    "</td>",
+    r#"<td class="description">"#,
+    "<td>function</td>",
+    "<td>object</td>",
+    "<td>integer or array of string</td>",
+    "<td>enum of \"a\", \"b\", or \"c\"</td>",
+    "<td>-1</td>",
+    "<td>\"</td>",
+    "<td>name with spaces</td>",
+    r#"<td><span class="optional">optional</span> value</td>"#,
+    r#"<span class="optional">optional</span>"#,
+    "<dl><dt><code>\"x\"</code></dt><dd>y</dd></dl>",
+    "<code>\"tabs\"</code>",
+    "<pre>function() {...};</pre>",
+    "<p>Since Chrome 12. Deprecated since Chrome 40. ChromeOS only.</p>",
+    "<p>",
+    "</p>",
+    "text",
+    " or ",
+    "(",
+    ")",
+    "array of ",
+];
+
+const WORDS: &[&str] = &[
+    "array", "of", "or", "enum", ",", "(", ")", "integer", "string", "\"a\"", "\"b\",", "tabs.Tab",
+    "object", "any", "", "  ", "of(", "or)", "binary",
+];
+
+fn fixture(layout: &str, name: &str) -> String {
+    let path = format!(
+        "{}/tests/fixtures/{}/{}.html",
+        env!("CARGO_MANIFEST_DIR"),
+        layout,
+        name
+    );
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn char_boundary(text: &str, rng: &mut Lcg) -> usize {
+    let mut i = rng.below(text.len() + 1);
+    while !text.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+fn mutate(text: &str, rng: &mut Lcg) -> String {
+    let mut text = text.to_owned();
+    for _ in 0..1 + rng.below(8) {
+        let a = char_boundary(&text, rng);
+        let b = char_boundary(&text, rng);
+        let (start, end) = (a.min(b), a.max(b));
+        text = match rng.below(3) {
+            0 => format!("{}{}", &text[..start], &text[end..]),
+            1 => format!("{}{}{}", &text[..end], &text[start..end], &text[end..]),
+            _ => format!(
+                "{}{}{}",
+                &text[..start],
+                rng.pick(FRAGMENTS),
+                &text[start..]
+            ),
+        };
+    }
+    text
+}
+
+fn soup(rng: &mut Lcg) -> String {
+    (0..rng.below(120))
+        .map(|_| rng.pick(FRAGMENTS))
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+fn assert_no_panic(input: &str, f: impl FnOnce() + panic::UnwindSafe) {
+    if panic::catch_unwind(f).is_err() {
+        panic!("panicked on input:\n{}", input);
+    }
+}
+
+#[test]
+fn parser_never_panics() {
+    let fixtures = [
+        fixture("legacy", "tabs"),
+        fixture("legacy", "input.ime"),
+        fixture("current", "tabs"),
+        fixture("current", "input.ime"),
+    ];
+    let mut rng = Lcg(0x5eed);
+    for i in 0..600 {
+        let html = if i % 3 == 0 {
+            soup(&mut rng)
+        } else {
+            let base = &fixtures[rng.below(fixtures.len())];
+            mutate(base, &mut rng)
+        };
+
+        for strict in [false, true].iter() {
+            let input = html.clone();
+            assert_no_panic(&html, move || {
+                let options = ParseOptions::default().with_strict(*strict);
+                let _ = webext_parser::parse_api_html_with("fuzz", &input, &options);
+            });
+        }
+    }
+}
+
+#[test]
+fn type_ref_parse_never_panics() {
+    let mut rng = Lcg(42);
+    for _ in 0..5000 {
+        let text = (0..rng.below(8))
+            .map(|_| rng.pick(WORDS))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let input = text.clone();
+        assert_no_panic(&text, move || {
+            if let Ok(type_ref) = TypeRef::parse(&input) {
+                let _ = type_ref.to_string();
+            }
+        });
+    }
+}
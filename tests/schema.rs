@@ -0,0 +1,495 @@
+mod common;
+
+use common::{arg, method, namespace};
+use std::path::PathBuf;
+use webext_parser::api::{
+    Argument, ArgumentKind, Float, Literal, Method, Namespace, Primitive, PropertyKind, TypeKind,
+    TypeRef,
+};
+use webext_parser::schema::{self, SchemaErrorKind, SchemaOptions};
+
+fn dir() -> PathBuf {
+    common::fixtures().join("schema/chromium")
+}
+
+fn load(options: &SchemaOptions) -> Vec<Namespace> {
+    let (namespaces, diagnostics) = schema::load_dir(&dir(), options).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    namespaces
+}
+
+#[test]
+fn loads_directory() {
+    let namespaces = load(&SchemaOptions::default());
+    let names = namespaces.iter().map(|n| n.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["alarms", "input.ime", "tabs"]);
+
+    let with_nodoc = load(&SchemaOptions::default().with_nodoc(true));
+    let names = with_nodoc.iter().map(|n| n.name()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "alarms",
+            "input.ime",
+            "input.internal",
+            "tabs",
+            "testInternal"
+        ]
+    );
+    assert_eq!(
+        method(namespace(&with_nodoc, "tabs"), "sendRequest")
+            .args()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn types() {
+    let namespaces = load(&SchemaOptions::default());
+    let tabs = namespace(&namespaces, "tabs");
+    assert_eq!(
+        tabs.description(),
+        Some("Use the `chrome.tabs` API to interact with the browser's tab system. You can use this API to create, modify, and rearrange tabs in the browser.")
+    );
+
+    match tabs.types()[0].kind() {
+        TypeKind::Enum { variants } => {
+            assert_eq!(variants.len(), 3);
+            assert_eq!(variants[0].value(), "user");
+            assert_eq!(
+                variants[0].description(),
+                Some("A user input action set the muted state.")
+            );
+        }
+        _ => panic!("enum expected"),
+    }
+
+    let tab = tabs.types().iter().find(|t| t.name() == "Tab").unwrap();
+    match tab.kind() {
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            ..
+        } => {
+            assert_eq!(elements.len(), 3);
+            assert!(optional_elements
+                .iter()
+                .all(|e| e.name() != "internalState"));
+            let id = optional_elements.iter().find(|e| e.name() == "id").unwrap();
+            assert_eq!(
+                id.description(),
+                Some("The ID of the tab. Tab ID can also be set to `tabs.TAB_ID_NONE` for apps and devtools windows.")
+            );
+            let url = optional_elements
+                .iter()
+                .find(|e| e.name() == "url")
+                .unwrap();
+            assert!(url
+                .description()
+                .unwrap()
+                .contains("https://example.com/a//b"));
+            let selected = elements.iter().find(|e| e.name() == "selected").unwrap();
+            assert_eq!(
+                selected.availability().deprecation(),
+                Some("Please use `tabs.Tab.highlighted`.")
+            );
+        }
+        _ => panic!("struct expected"),
+    }
+
+    match tabs.properties()[0].kind() {
+        PropertyKind::Immediate { type_ref, value } => {
+            assert_eq!(type_ref.to_string(), "integer");
+            assert_eq!(value.as_ref().unwrap().to_string(), "-1");
+        }
+        _ => panic!("immediate property expected"),
+    }
+}
+
+#[test]
+fn functions() {
+    let namespaces = load(&SchemaOptions::default());
+    let tabs = namespace(&namespaces, "tabs");
+    assert!(tabs.methods().iter().all(|m| m.name() != "sendRequest"));
+
+    let query = method(tabs, "query");
+    assert!(query.supports_promise());
+    assert_eq!(
+        query.promise_type(),
+        Some(TypeRef::Array(Box::new(TypeRef::Named("Tab".to_owned()))))
+    );
+    match arg(query, "queryInfo") {
+        TypeRef::Object(Some(t)) => {
+            assert_eq!(t.name(), "QueryInfo");
+            match t.kind() {
+                TypeKind::Struct {
+                    optional_elements, ..
+                } => {
+                    let url = optional_elements
+                        .iter()
+                        .find(|e| e.name() == "url")
+                        .unwrap();
+                    assert_eq!(url.type_ref().to_string(), "string or array of string");
+                }
+                _ => panic!("struct expected"),
+            }
+        }
+        t => panic!("unexpected type {}", t),
+    }
+
+    let capture = method(tabs, "captureVisibleTab");
+    assert!(!capture.supports_promise());
+    assert!(capture.args()[1].is_optional());
+
+    let on_created = &tabs.events()[0];
+    assert_eq!(
+        arg(on_created.callback(), "tab"),
+        &TypeRef::Named("Tab".to_owned())
+    );
+}
+
+#[test]
+fn platforms_and_inline_objects() {
+    let namespaces = load(&SchemaOptions::default());
+    let ime = namespace(&namespaces, "input.ime");
+    assert_eq!(ime.availability().platforms(), ["chromeos"]);
+
+    let parameters = match arg(method(ime, "setCandidates"), "parameters") {
+        TypeRef::Object(Some(t)) => t,
+        t => panic!("unexpected type {}", t),
+    };
+    let candidates = match parameters.kind() {
+        TypeKind::Struct { elements, .. } => elements.iter().find(|e| e.name() == "candidates"),
+        _ => None,
+    }
+    .unwrap();
+    match candidates.type_ref() {
+        TypeRef::Array(item) => match &**item {
+            TypeRef::Object(Some(t)) => assert_eq!(t.name(), "ParametersCandidates"),
+            t => panic!("unexpected item type {}", t),
+        },
+        t => panic!("unexpected type {}", t),
+    }
+}
+
+#[test]
+fn inline_argument_names() {
+    let (namespaces, _) = schema::parse_schema(
+        r#"[{"namespace": "x", "types": [{"id": "Filter", "type": "string"}], "functions": [
+            {"name": "create", "type": "function", "parameters": [
+                {"name": "options", "type": "object", "properties": {"url": {"type": "string"}}}
+            ]},
+            {"name": "update", "type": "function", "parameters": [
+                {"name": "options", "type": "object", "properties": {"muted": {"type": "boolean"}}}
+            ]},
+            {"name": "find", "type": "function", "parameters": [
+                {"name": "filter", "type": "object", "properties": {"url": {"type": "string"}}},
+                {"name": "range", "type": "object", "properties": {"start": {"type": "integer"}}}
+            ]}
+        ]}]"#,
+        &SchemaOptions::default(),
+    )
+    .unwrap();
+    let x = namespace(&namespaces, "x");
+    let name = |method: &str, arg_name: &str| match arg(self::method(x, method), arg_name) {
+        TypeRef::Object(Some(t)) => t.name().to_owned(),
+        t => panic!("unexpected type {}", t),
+    };
+    assert_eq!(name("create", "options"), "CreateOptions");
+    assert_eq!(name("update", "options"), "UpdateOptions");
+    // Only the inline type clashing with a declared one is renamed.
+    assert_eq!(name("find", "filter"), "FindFilter");
+    assert_eq!(name("find", "range"), "Range");
+}
+
+#[test]
+fn listener_methods() {
+    let (namespaces, _) = schema::parse_schema(
+        r#"[{"namespace": "webRequest", "events": [
+            {"name": "onBeforeRequest", "type": "function",
+             "parameters": [{"name": "details", "type": "object", "properties": {"url": {"type": "string"}}}],
+             "extraParameters": [
+                {"name": "filter", "$ref": "RequestFilter"},
+                {"name": "extraInfoSpec", "type": "array", "optional": true, "items": {"type": "string"}}
+             ]}
+        ]}]"#,
+        &SchemaOptions::default(),
+    )
+    .unwrap();
+    let event = &namespace(&namespaces, "webRequest").events()[0];
+    let callback = |method: &Method| match method.args()[0].kind() {
+        ArgumentKind::Callback { callback } => callback.clone(),
+        _ => panic!("callback expected"),
+    };
+
+    let add = event.add_listener();
+    assert_eq!(add.name(), "addListener");
+    assert_eq!(
+        param_names(add.args()),
+        ["callback", "filter", "extraInfoSpec"]
+    );
+    assert_eq!(&callback(&add), event.callback());
+    assert!(add.returns().is_none());
+
+    // Removing and checking a listener only take the callback.
+    let remove = event.remove_listener();
+    assert_eq!(remove.name(), "removeListener");
+    assert_eq!(param_names(remove.args()), ["callback"]);
+    assert_eq!(&callback(&remove), event.callback());
+    assert!(remove.returns().is_none());
+
+    let has = event.has_listener();
+    assert_eq!(has.name(), "hasListener");
+    assert_eq!(param_names(has.args()), ["callback"]);
+    assert_eq!(&callback(&has), event.callback());
+    assert_eq!(
+        has.returns().unwrap().type_ref(),
+        &TypeRef::Primitive(Primitive::Boolean)
+    );
+    assert_eq!(param_names(event.callback().args()), ["details"]);
+}
+
+fn param_names(args: &[Argument]) -> Vec<&str> {
+    args.iter()
+        .map(|a| match a.kind() {
+            ArgumentKind::Element { element } => element.name(),
+            ArgumentKind::Callback { callback } => callback.name(),
+        })
+        .collect()
+}
+
+#[test]
+fn promise_params() {
+    let namespaces = load(&SchemaOptions::default());
+    let query = method(namespace(&namespaces, "tabs"), "query");
+    assert_eq!(param_names(query.promise_params().unwrap()), ["result"]);
+
+    // Firefox resolves the promise of a callback with several parameters to an array.
+    let (namespaces, _) = schema::parse_schema(
+        r#"[{"namespace": "runtime", "functions": [
+            {"name": "requestUpdateCheck", "type": "function", "async": "callback", "parameters": [
+                {"name": "callback", "type": "function", "parameters": [
+                    {"name": "status", "$ref": "RequestUpdateCheckStatus"},
+                    {"name": "details", "type": "object", "optional": true,
+                     "properties": {"version": {"type": "string"}}}
+                ]}
+            ]}
+        ]}]"#,
+        &SchemaOptions::default(),
+    )
+    .unwrap();
+    let check = method(namespace(&namespaces, "runtime"), "requestUpdateCheck");
+    assert_eq!(
+        param_names(check.promise_params().unwrap()),
+        ["status", "details"]
+    );
+    assert_eq!(
+        check.promise_type().unwrap().to_string(),
+        "array of (RequestUpdateCheckStatus or object)"
+    );
+    assert!(check.promise_args().is_empty());
+}
+
+#[test]
+fn errors() {
+    let options = SchemaOptions::default();
+    let error = schema::parse_schema("[{\"namespace\": \"x\",}]", &options).unwrap_err();
+    assert!(matches!(error.kind(), SchemaErrorKind::Json(_)));
+
+    let error = schema::parse_schema(
+        r#"[{"namespace": "x", "types": [{"id": "T", "type": "object", "properties": {"a": {"type": "tuple"}}}]}]"#,
+        &options,
+    )
+    .unwrap_err();
+    assert_eq!(error.namespace(), Some("x"));
+    assert_eq!(error.to_string(), "x: unsupported type \"tuple\"");
+}
+
+#[test]
+fn idl() {
+    let namespaces = load(&SchemaOptions::default());
+    let alarms = namespace(&namespaces, "alarms");
+    assert_eq!(
+        alarms.description(),
+        Some("Use the `chrome.alarms` API to schedule code to run periodically or at a specified time in the future.")
+    );
+
+    let names = alarms.types().iter().map(|t| t.name()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["Alarm", "AlarmCreateInfo", "QueueState", "Snapshot"]
+    );
+    match alarms.types()[0].kind() {
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            ..
+        } => {
+            assert_eq!(elements.len(), 2);
+            assert_eq!(
+                elements[1].description(),
+                Some("Time at which this alarm was scheduled to fire, in milliseconds past the epoch (e.g. `Date.now() + n`).")
+            );
+            assert_eq!(optional_elements[0].name(), "periodInMinutes");
+        }
+        _ => panic!("struct expected"),
+    }
+    match alarms.types()[1].kind() {
+        TypeKind::Struct {
+            optional_elements, ..
+        } => {
+            assert_eq!(
+                optional_elements[1].description(),
+                Some("Length of time in minutes after which the `onAlarm` event should fire.")
+            );
+            assert_eq!(optional_elements[2].description(), None);
+        }
+        _ => panic!("struct expected"),
+    }
+    match alarms.types()[2].kind() {
+        TypeKind::Enum { variants } => {
+            assert_eq!(variants.len(), 2);
+            assert_eq!(variants[1].description(), Some("Alarms are held back."));
+        }
+        _ => panic!("enum expected"),
+    }
+    match alarms.types()[3].kind() {
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            ..
+        } => {
+            assert_eq!(elements[0].type_ref(), &TypeRef::Named("Blob".to_owned()));
+            assert_eq!(
+                elements[1].type_ref().to_string(),
+                "array of (string or integer)"
+            );
+            assert_eq!(optional_elements[0].type_ref(), &TypeRef::Binary);
+        }
+        _ => panic!("struct expected"),
+    }
+
+    let names = alarms
+        .properties()
+        .iter()
+        .map(|p| p.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["MAX_ALARMS", "state"]);
+    match alarms.properties()[0].kind() {
+        PropertyKind::Immediate { value, .. } => {
+            assert_eq!(value.as_ref().unwrap().to_string(), "500")
+        }
+        _ => panic!("immediate property expected"),
+    }
+}
+
+#[test]
+fn idl_functions() {
+    let namespaces = load(&SchemaOptions::default());
+    let alarms = namespace(&namespaces, "alarms");
+    let names = alarms
+        .methods()
+        .iter()
+        .map(|m| m.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["create", "get", "getAll", "clearAll", "count"]);
+
+    let create = method(alarms, "create");
+    assert!(create.supports_promise());
+    assert_eq!(
+        create.args()[0].description(),
+        Some("Optional name to identify this alarm. Defaults to the empty string.")
+    );
+    assert!(create.args()[0].is_optional());
+    assert_eq!(
+        arg(create, "alarmInfo"),
+        &TypeRef::Named("AlarmCreateInfo".to_owned())
+    );
+    let callback = create.callback().unwrap();
+    assert_eq!(callback.name(), "callback");
+    assert!(create.args()[2].is_optional());
+    assert_eq!(
+        arg(callback, "wasCleared"),
+        &TypeRef::Primitive(webext_parser::api::Primitive::Boolean)
+    );
+
+    let get_all = method(alarms, "getAll");
+    assert_eq!(
+        get_all.promise_type(),
+        Some(TypeRef::Array(Box::new(TypeRef::Named("Alarm".to_owned()))))
+    );
+    assert_eq!(
+        get_all.callback().unwrap().args()[0].description(),
+        Some("Every alarm that is currently scheduled.")
+    );
+
+    let clear_all = method(alarms, "clearAll");
+    assert!(clear_all.supports_promise());
+    assert_eq!(
+        clear_all.promise_type(),
+        Some(TypeRef::Primitive(webext_parser::api::Primitive::Boolean))
+    );
+
+    let count = method(alarms, "count");
+    assert!(!count.supports_promise());
+    assert_eq!(count.returns().unwrap().type_ref().to_string(), "integer");
+    assert_eq!(count.availability().platforms(), ["chromeos", "win"]);
+    assert_eq!(
+        count.availability().deprecation(),
+        Some("Use `getAll` instead.")
+    );
+
+    let on_alarm = &alarms.events()[0];
+    assert_eq!(on_alarm.name(), "onAlarm");
+    assert_eq!(
+        on_alarm.description(),
+        Some("Fired when an alarm has elapsed. Useful for event pages.")
+    );
+    assert_eq!(
+        on_alarm.callback().args()[0].description(),
+        Some("The alarm that has elapsed.")
+    );
+}
+
+#[test]
+fn idl_errors() {
+    let options = SchemaOptions::default();
+    let error = schema::parse_idl(
+        "namespace x {\n  dictionary D {\n    long;\n  };\n};",
+        &options,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 3: expected an identifier, found Punct(';')"
+    );
+
+    let error =
+        schema::parse_idl("namespace x { dictionary D { void v; }; };", &options).unwrap_err();
+    assert_eq!(error.namespace(), Some("x"));
+}
+
+#[test]
+fn float_constants() {
+    assert_eq!(Float::new(0.5).map(Float::get), Some(0.5));
+    assert!(Float::new(f64::NAN).is_none());
+    assert!(Float::new(f64::INFINITY).is_none());
+
+    let options = SchemaOptions::default();
+    let namespaces =
+        schema::parse_idl("namespace x {\n  const double RATIO = 0.5;\n};", &options).unwrap();
+    match namespaces[0].properties()[0].kind() {
+        PropertyKind::Immediate { value, .. } => {
+            let value = value.as_ref().unwrap();
+            assert_eq!(value, &Literal::Float(Float::new(0.5).unwrap()));
+            assert_eq!(value.to_string(), "0.5");
+        }
+        _ => panic!("immediate property expected"),
+    }
+
+    // Too large for an `f64`, so it would parse as infinity.
+    let error =
+        schema::parse_idl("namespace x {\n  const double HUGE = 1e999;\n};", &options).unwrap_err();
+    assert_eq!(error.to_string(), "x: invalid constant Number(\"1e999\")");
+}
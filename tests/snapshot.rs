@@ -0,0 +1,83 @@
+#![cfg(feature = "serde")]
+
+use std::path::PathBuf;
+use webext_parser::api::{Float, Literal, Namespace};
+use webext_parser::builder::{NamespaceBuilder, PropertyBuilder};
+use webext_parser::schema::{self, SchemaOptions};
+use webext_parser::snapshot::{Snapshot, VERSION};
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+// Pages and schemas between them use every part of the model.
+fn namespaces() -> Vec<Namespace> {
+    let options = SchemaOptions::default();
+    let (mut namespaces, _) =
+        schema::load_dir(&fixtures().join("schema/chromium"), &options).unwrap();
+    let firefox = fixtures().join("schema/firefox");
+    let (firefox, _) = schema::load_dirs(
+        &[&firefox.join("toolkit"), &firefox.join("browser")],
+        &options,
+    )
+    .unwrap();
+    namespaces.extend(firefox);
+    for (layout, name) in [("legacy", "tabs"), ("current", "input.ime")] {
+        let path = fixtures().join(layout).join(format!("{}.html", name));
+        let html = std::fs::read_to_string(path).unwrap();
+        namespaces.push(webext_parser::parse_api_html(name, &html).unwrap());
+    }
+    let ratio = Literal::Float(Float::new(0.5).unwrap());
+    namespaces.push(
+        NamespaceBuilder::new("constants")
+            .property(
+                PropertyBuilder::new_constant("RATIO", ratio)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+    namespaces
+}
+
+#[test]
+fn round_trip() {
+    let namespaces = namespaces();
+    let snapshot = Snapshot::new(namespaces.clone());
+    assert_eq!(snapshot.version(), VERSION);
+
+    let json = snapshot.to_json().unwrap();
+    let loaded = Snapshot::from_json(&json).unwrap();
+    assert_eq!(loaded, snapshot);
+    assert_eq!(loaded.into_namespaces(), namespaces);
+}
+
+#[test]
+fn rejects_other_versions() {
+    let json = Snapshot::new(vec![]).to_json().unwrap();
+    let old = json.replace(
+        &format!("\"version\": {}", VERSION),
+        &format!("\"version\": {}", VERSION - 1),
+    );
+    assert_ne!(old, json);
+    let error = Snapshot::from_json(&old).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "unsupported snapshot version {} (expected {})",
+            VERSION - 1,
+            VERSION
+        )
+    );
+
+    assert!(Snapshot::from_json("{\"namespaces\": []}").is_err());
+
+    // A real version 1 snapshot, whose namespaces lack fields added since.
+    let v1 = std::fs::read_to_string(fixtures().join("snapshot/v1.json")).unwrap();
+    let error = Snapshot::from_json(&v1).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!("unsupported snapshot version 1 (expected {})", VERSION)
+    );
+}
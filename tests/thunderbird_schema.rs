@@ -0,0 +1,132 @@
+mod common;
+
+use common::{fixtures, namespace, type_named};
+use webext_parser::api::{ArgumentKind, Namespace, Type, TypeKind, TypeRef};
+use webext_parser::link;
+use webext_parser::schema::{self, SchemaOptions};
+use webext_parser::tree::NamespaceTree;
+
+// Thunderbird's APIs loaded over the Firefox ones, returned as `(mail, base)`.
+fn load() -> (Vec<Namespace>, Vec<Namespace>) {
+    let options = SchemaOptions::default();
+    let toolkit = fixtures().join("schema/firefox/toolkit");
+    let browser = fixtures().join("schema/firefox/browser");
+    let (mut base, _) = schema::load_dirs(&[&toolkit, &browser], &options).unwrap();
+    let mail = fixtures().join("schema/thunderbird");
+    let (namespaces, diagnostics) =
+        schema::load_dirs_with_base(&[&mail], &mut base, &options).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    (namespaces, base)
+}
+
+fn element_type<'a>(t: &'a Type, name: &str) -> &'a TypeRef {
+    match t.kind() {
+        TypeKind::Struct {
+            elements,
+            optional_elements,
+            ..
+        } => elements
+            .iter()
+            .chain(optional_elements)
+            .find(|e| e.name() == name)
+            .unwrap()
+            .type_ref(),
+        _ => panic!("struct expected"),
+    }
+}
+
+#[test]
+fn loads_mail_namespaces() {
+    let (namespaces, _) = load();
+    let names = namespaces.iter().map(|n| n.name()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "accounts",
+            "compose",
+            "composeAction",
+            "folders",
+            "messages",
+            "tabs"
+        ]
+    );
+
+    let tree = NamespaceTree::from_namespaces("messenger", namespaces);
+    assert_eq!(
+        tree.get("messages").unwrap().js_path(),
+        "messenger.messages"
+    );
+
+    let accounts = tree.get("accounts").unwrap().namespace().unwrap();
+    assert_eq!(accounts.permissions(), ["accountsRead"]);
+    assert!(accounts.methods()[0].supports_promise());
+}
+
+#[test]
+fn extends_and_imports_base_namespaces() {
+    let (namespaces, base) = load();
+    let manifest = namespace(&base, "manifest");
+    match type_named(manifest, "PermissionNoPrompt").kind() {
+        TypeKind::Enum { variants } => {
+            assert!(variants.iter().any(|v| v.value() == "accountsRead"));
+        }
+        _ => panic!("enum expected"),
+    }
+    let compose_action = element_type(
+        type_named(manifest, "WebExtensionManifest"),
+        "compose_action",
+    );
+    assert!(matches!(compose_action, TypeRef::Object(Some(_))));
+
+    let compose_action = namespace(&namespaces, "composeAction");
+    assert_eq!(
+        compose_action.description(),
+        Some("Use a compose action to put a button in the message composition toolbar.")
+    );
+    assert_eq!(compose_action.manifest_keys(), ["compose_action"]);
+    let methods = compose_action
+        .methods()
+        .iter()
+        .map(|m| m.name())
+        .collect::<Vec<_>>();
+    assert_eq!(methods, ["setTitle", "openPopup"]);
+}
+
+#[test]
+fn links_against_shared_types() {
+    let (mut namespaces, base) = load();
+    let diagnostics = link::link_with(&mut namespaces, &base);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let messages = namespace(&namespaces, "messages");
+    let header = type_named(messages, "MessageHeader");
+    assert_eq!(
+        element_type(header, "date"),
+        &TypeRef::Named("extensionTypes.Date".to_owned())
+    );
+    assert_eq!(
+        element_type(header, "folder"),
+        &TypeRef::Named("folders.MailFolder".to_owned())
+    );
+    assert_eq!(
+        element_type(type_named(messages, "MessageList"), "messages"),
+        &TypeRef::Array(Box::new(TypeRef::Named(
+            "messages.MessageHeader".to_owned()
+        )))
+    );
+
+    let begin_new = &namespace(&namespaces, "compose").methods()[0];
+    let tab = match begin_new.callback().unwrap().args()[0].kind() {
+        ArgumentKind::Element { element } => element.type_ref(),
+        _ => panic!("element expected"),
+    };
+    assert_eq!(tab, &TypeRef::Named("tabs.Tab".to_owned()));
+
+    // Without the shared namespaces, references into them dangle.
+    let (mut namespaces, _) = load();
+    let dangling = link::link(&mut namespaces)
+        .iter()
+        .map(|d| d.reference().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(dangling, ["extensionTypes.Date"]);
+}
@@ -0,0 +1,84 @@
+use webext_parser::api::Namespace;
+use webext_parser::builder::NamespaceBuilder;
+use webext_parser::tree::NamespaceTree;
+
+fn namespace(name: &str) -> Namespace {
+    NamespaceBuilder::new(name).build().unwrap()
+}
+
+fn tree() -> NamespaceTree {
+    NamespaceTree::from_namespaces(
+        "chrome",
+        ["input.ime", "app.runtime", "app.window", "tabs", "app"]
+            .iter()
+            .map(|name| namespace(name))
+            .collect(),
+    )
+}
+
+#[test]
+fn nests_dotted_names() {
+    let tree = tree();
+    let root = tree.root();
+    assert!(root.is_root());
+    assert_eq!(root.js_path(), "chrome");
+    let top = root.children().map(|n| n.name()).collect::<Vec<_>>();
+    assert_eq!(top, ["input", "app", "tabs"]);
+
+    // `input` only exists as the parent of `input.ime`.
+    let input = tree.get("input").unwrap();
+    assert!(input.namespace().is_none());
+    let ime = tree.get("input.ime").unwrap();
+    assert_eq!(ime.name(), "ime");
+    assert_eq!(ime.path(), "input.ime");
+    assert_eq!(ime.js_path(), "chrome.input.ime");
+    assert_eq!(ime.namespace().unwrap().name(), "input.ime");
+    assert_eq!(ime.parent().unwrap().path(), "input");
+    assert!(ime.parent().unwrap().parent().unwrap().is_root());
+
+    // `app` is both an API and the parent of `app.runtime` and `app.window`.
+    let app = tree.get("app").unwrap();
+    assert_eq!(app.namespace().unwrap().name(), "app");
+    let children = app.children().map(|n| n.path()).collect::<Vec<_>>();
+    assert_eq!(children, ["app.runtime", "app.window"]);
+    assert_eq!(
+        app.child("runtime").unwrap().namespace().unwrap().name(),
+        "app.runtime"
+    );
+
+    assert!(tree.get("app.missing").is_none());
+    assert!(tree.get("ime").is_none());
+}
+
+#[test]
+fn iterates_parents_first() {
+    let tree = tree();
+    let paths = tree.iter().map(|n| n.path()).collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "input",
+            "input.ime",
+            "app",
+            "app.runtime",
+            "app.window",
+            "tabs"
+        ]
+    );
+    let names = tree.namespaces().map(|n| n.name()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["input.ime", "app", "app.runtime", "app.window", "tabs"]
+    );
+}
+
+#[test]
+fn insert_replaces_namespaces() {
+    let mut tree = tree();
+    assert!(tree.insert(namespace("input")).is_none());
+    assert!(tree.get("input").unwrap().namespace().is_some());
+
+    let replaced = tree.insert(namespace("app.runtime")).unwrap();
+    assert_eq!(replaced.name(), "app.runtime");
+    assert_eq!(tree.namespaces().count(), 6);
+}
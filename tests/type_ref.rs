@@ -0,0 +1,161 @@
+mod common;
+
+use common::{arg, method};
+use webext_parser::api::{ArgumentKind, Element, Namespace, Primitive, TypeKind, TypeRef};
+use webext_parser::visit::{self, Visitor};
+
+fn fixture(name: &str) -> Namespace {
+    webext_parser::parse_api_html(name, &common::html("legacy", name)).unwrap()
+}
+
+// Every element's type, including the fields of inline objects.
+#[derive(Default)]
+struct ElementTypes<'ast> {
+    types: Vec<&'ast TypeRef>,
+}
+
+impl<'ast> Visitor<'ast> for ElementTypes<'ast> {
+    fn visit_element(&mut self, element: &'ast Element) {
+        self.types.push(element.type_ref());
+        visit::walk_element(self, element);
+    }
+}
+
+fn type_refs(namespace: &Namespace) -> Vec<&TypeRef> {
+    let mut collector = ElementTypes::default();
+    collector.visit_namespace(namespace);
+    collector.types
+}
+
+#[test]
+fn fixture_rows_round_trip() {
+    for name in &["tabs", "input.ime"] {
+        let namespace = fixture(name);
+        let types = type_refs(&namespace);
+        assert!(!types.is_empty());
+        for t in types {
+            let text = t.to_string();
+            assert_eq!(TypeRef::parse(&text).unwrap().to_string(), text);
+        }
+    }
+}
+
+#[test]
+fn fixture_arrays() {
+    let tabs = fixture("tabs");
+    let result = match method(&tabs, "query").args()[1].kind() {
+        ArgumentKind::Callback { callback } => arg(callback, "result"),
+        _ => panic!("callback expected"),
+    };
+    assert_eq!(
+        result,
+        &TypeRef::Array(Box::new(TypeRef::Named("Tab".to_owned())))
+    );
+
+    let ime = fixture("input.ime");
+    let parameters = match arg(method(&ime, "setCandidates"), "parameters") {
+        TypeRef::Object(Some(t)) => t,
+        t => panic!("unexpected type {}", t),
+    };
+    assert_eq!(parameters.name(), "Parameters");
+    let candidates = match parameters.kind() {
+        TypeKind::Struct { elements, .. } => elements.iter().find(|e| e.name() == "candidates"),
+        _ => None,
+    }
+    .unwrap();
+    match candidates.type_ref() {
+        TypeRef::Array(item) => match &**item {
+            TypeRef::Object(Some(t)) => {
+                assert_eq!(t.name(), "ParametersCandidates");
+                match t.kind() {
+                    TypeKind::Struct {
+                        elements,
+                        optional_elements,
+                        ..
+                    } => {
+                        assert_eq!(elements.len(), 2);
+                        assert_eq!(optional_elements.len(), 3);
+                        assert_eq!(
+                            optional_elements[2].type_ref().rustify(),
+                            "ParametersCandidatesUsage"
+                        );
+                    }
+                    _ => panic!("struct expected"),
+                }
+            }
+            t => panic!("unexpected item type {}", t),
+        },
+        t => panic!("unexpected type {}", t),
+    }
+}
+
+#[test]
+fn inline_objects() {
+    let tabs = fixture("tabs");
+    match arg(method(&tabs, "query"), "queryInfo") {
+        TypeRef::Object(Some(t)) => {
+            assert_eq!(t.name(), "QueryInfo");
+            match t.kind() {
+                TypeKind::Struct {
+                    elements,
+                    optional_elements,
+                    ..
+                } => {
+                    assert!(elements.is_empty());
+                    let url = optional_elements
+                        .iter()
+                        .find(|e| e.name() == "url")
+                        .unwrap();
+                    assert_eq!(url.type_ref().to_string(), "string or array of string");
+                }
+                _ => panic!("struct expected"),
+            }
+        }
+        t => panic!("unexpected type {}", t),
+    }
+}
+
+#[test]
+fn nested_arrays() {
+    let integer = TypeRef::Primitive(Primitive::Integer);
+    let cases = vec![
+        (
+            "array of array of integer",
+            TypeRef::Array(Box::new(TypeRef::Array(Box::new(integer.clone())))),
+        ),
+        (
+            "array of array of array of tabs.Tab",
+            TypeRef::Array(Box::new(TypeRef::Array(Box::new(TypeRef::Array(
+                Box::new(TypeRef::Named("tabs.Tab".to_owned())),
+            ))))),
+        ),
+        (
+            r#"array of enum of "blocking", "requestHeaders", or "extraHeaders""#,
+            TypeRef::Array(Box::new(TypeRef::Enum(vec![
+                "blocking".to_owned(),
+                "requestHeaders".to_owned(),
+                "extraHeaders".to_owned(),
+            ]))),
+        ),
+        (
+            "integer or array of array of integer",
+            TypeRef::Union(vec![
+                integer.clone(),
+                TypeRef::Array(Box::new(TypeRef::Array(Box::new(integer.clone())))),
+            ]),
+        ),
+        (
+            "array of (integer or string)",
+            TypeRef::Array(Box::new(TypeRef::Union(vec![
+                integer,
+                TypeRef::Primitive(Primitive::String),
+            ]))),
+        ),
+    ];
+
+    for (text, expected) in cases {
+        let parsed = TypeRef::parse(text).unwrap();
+        assert_eq!(parsed, expected);
+        assert_eq!(parsed.to_string(), text);
+    }
+}
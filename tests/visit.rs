@@ -0,0 +1,175 @@
+use webext_parser::api::{
+    Argument, ArgumentKind, Element, EnumVariant, Event, Method, Namespace, Property, Returns,
+    Type, TypeKind, TypeRef,
+};
+use webext_parser::schema::{self, SchemaOptions};
+use webext_parser::visit::{self, Visitor, VisitorMut};
+
+// One of each kind of node, with a callback taking a callback.
+fn sample() -> Namespace {
+    let (mut namespaces, _) = schema::parse_schema(
+        r#"[{"namespace": "sample",
+            "types": [
+                {"id": "Color", "type": "string", "enum": ["red", "green"]},
+                {"id": "Area", "type": "object",
+                 "properties": {"name": {"type": "string"}, "color": {"$ref": "Color", "optional": true}},
+                 "functions": [{"name": "clear", "type": "function", "parameters": []}],
+                 "events": [{"name": "onCleared", "type": "function", "parameters": []}]}
+            ],
+            "properties": {
+                "MAX": {"value": 5},
+                "local": {"functions": [
+                    {"name": "get", "type": "function", "parameters": [{"name": "key", "type": "string"}]}
+                ]}
+            },
+            "functions": [
+                {"name": "watch", "type": "function", "parameters": [
+                    {"name": "options", "type": "object",
+                     "properties": {"colors": {"type": "array", "items": {"$ref": "Color"}}}},
+                    {"name": "outer", "type": "function", "parameters": [
+                        {"name": "inner", "type": "function", "parameters": [{"name": "color", "$ref": "Color"}]}
+                    ]}
+                ], "returns": {"choices": [{"$ref": "Color"}, {"type": "integer"}]}}
+            ],
+            "events": [
+                {"name": "onChanged", "type": "function", "parameters": [{"name": "color", "$ref": "Color"}]}
+            ]
+        }]"#,
+        &SchemaOptions::default(),
+    )
+    .unwrap();
+    namespaces.remove(0)
+}
+
+#[derive(Default)]
+struct Counter<'ast> {
+    types: Vec<&'ast str>,
+    variants: usize,
+    elements: Vec<&'ast str>,
+    named: Vec<&'ast str>,
+    properties: Vec<&'ast str>,
+    methods: Vec<&'ast str>,
+    arguments: usize,
+    returns: usize,
+    events: Vec<&'ast str>,
+}
+
+impl<'ast> Visitor<'ast> for Counter<'ast> {
+    fn visit_type(&mut self, t: &'ast Type) {
+        self.types.push(t.name());
+        visit::walk_type(self, t);
+    }
+
+    fn visit_enum_variant(&mut self, _variant: &'ast EnumVariant) {
+        self.variants += 1;
+    }
+
+    fn visit_element(&mut self, element: &'ast Element) {
+        self.elements.push(element.name());
+        visit::walk_element(self, element);
+    }
+
+    fn visit_type_ref(&mut self, type_ref: &'ast TypeRef) {
+        if let TypeRef::Named(name) = type_ref {
+            self.named.push(name);
+        }
+        visit::walk_type_ref(self, type_ref);
+    }
+
+    fn visit_property(&mut self, property: &'ast Property) {
+        self.properties.push(property.name());
+        visit::walk_property(self, property);
+    }
+
+    fn visit_method(&mut self, method: &'ast Method) {
+        self.methods.push(method.name());
+        visit::walk_method(self, method);
+    }
+
+    fn visit_argument(&mut self, arg: &'ast Argument) {
+        self.arguments += 1;
+        visit::walk_argument(self, arg);
+    }
+
+    fn visit_returns(&mut self, returns: &'ast Returns) {
+        self.returns += 1;
+        visit::walk_returns(self, returns);
+    }
+
+    fn visit_event(&mut self, event: &'ast Event) {
+        self.events.push(event.name());
+        visit::walk_event(self, event);
+    }
+}
+
+#[test]
+fn reaches_every_node() {
+    let sample = sample();
+    let mut counter = Counter::default();
+    counter.visit_namespace(&sample);
+
+    assert_eq!(counter.types, ["Color", "Area", "Options"]);
+    assert_eq!(counter.variants, 2);
+    assert_eq!(
+        counter.elements,
+        ["name", "color", "key", "options", "colors", "color", "color"]
+    );
+    assert_eq!(counter.properties, ["MAX", "local"]);
+    // Struct methods and events, property object methods, then callbacks in callbacks.
+    assert_eq!(
+        counter.methods,
+        ["clear", "callback", "get", "watch", "outer", "inner", "callback"]
+    );
+    assert_eq!(counter.arguments, 6);
+    assert_eq!(counter.returns, 1);
+    assert_eq!(counter.events, ["onCleared", "onChanged"]);
+    assert_eq!(counter.named, ["Color"; 5]);
+}
+
+struct Rename<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl VisitorMut for Rename<'_> {
+    fn visit_type_ref_mut(&mut self, type_ref: &mut TypeRef) {
+        match type_ref {
+            TypeRef::Named(name) if name == self.from => *name = self.to.to_owned(),
+            _ => visit::walk_type_ref_mut(self, type_ref),
+        }
+    }
+}
+
+#[test]
+fn rewrites_nested_type_refs() {
+    let mut sample = sample();
+    Rename {
+        from: "Color",
+        to: "colors.Color",
+    }
+    .visit_namespace_mut(&mut sample);
+
+    let mut counter = Counter::default();
+    counter.visit_namespace(&sample);
+    assert_eq!(counter.named, ["colors.Color"; 5]);
+
+    // Inside an array inside an inline object, and inside a union.
+    let watch = &sample.methods()[0];
+    assert_eq!(
+        watch.returns().unwrap().type_ref().to_string(),
+        "colors.Color or integer"
+    );
+    let options = match watch.args()[0].kind() {
+        ArgumentKind::Element { element } => element.type_ref(),
+        _ => panic!("element expected"),
+    };
+    match options {
+        TypeRef::Object(Some(t)) => match t.kind() {
+            TypeKind::Struct { elements, .. } => {
+                assert_eq!(elements[0].type_ref().to_string(), "array of colors.Color")
+            }
+            _ => panic!("struct expected"),
+        },
+        t => panic!("unexpected type {}", t),
+    }
+}